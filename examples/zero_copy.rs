@@ -10,8 +10,16 @@ impl Clone for Dummy {
     }
 }
 
+// modify starts from the last published value, synchronized without a clone
+impl signal::SyncFrom for Dummy {
+    fn sync_from(&mut self, latest: &Self) {
+        self.id = latest.id
+    }
+}
+
 fn main() {
     let (mut source, sink) = signal::sync::create::<Dummy>();
+    source.sync_forward();
     source.modify(&mut |dummy| {dummy.id = 1});
 
     // run the consumer
@@ -34,11 +42,7 @@ fn main() {
             let state = source.modify(&mut |dummy| {
                 if dummy.id % 100 == 0 { println!("\nsrc dummy.id: {}", dummy.id) }
                 else { print!(".") }
-                // - counter intuitive, dummy id needs to be increased by 2
-                // - because the signal has two memory slots and write will modify in place
-                // - in combination with incrementing the id in one slot beforehand this
-                //   will give an id incrementing by 1 each cycle
-                dummy.id += 2;
+                dummy.id += 1;
             });
             use signal::sync::State::*;
             match state {
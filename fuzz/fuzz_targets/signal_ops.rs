@@ -0,0 +1,16 @@
+//! Applies operations decoded from the fuzz input to plain and synced signals, checking them
+//! against the reference model of the property tests.
+//! Run with `cargo +nightly fuzz run signal_ops` from the root of the repository.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../tests/model/mod.rs"]
+mod model;
+
+fuzz_target!(|data: &[u8]| {
+    let ops = model::decode(data);
+    model::run_plain(&ops);
+    model::run_sync(&ops);
+});
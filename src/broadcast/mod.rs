@@ -0,0 +1,90 @@
+pub mod sink;
+pub mod source;
+
+pub use source::Source;
+pub use sink::Sink;
+
+use crate::ring::Ring;
+use crate::signal::loom::atomic::AtomicBool;
+
+/// Create a pair of source and sink of a broadcast ring (single producer multiple consumer).
+/// In contrast to a signal every sink sees every value, as long as it keeps up with the source.
+/// Each sink has its own cursor into a ring of the last `capacity` values. The source is never
+/// blocked, a sink which falls behind gets a [Error::Lagged] instead.
+pub fn create<T>(capacity: usize) -> (Source<T>, Sink<T>) where T: Clone + Send + Sync {
+    let source = Source::new(capacity);
+    let sink = source.sink();
+    (source, sink)
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Error {
+    /// No new value has been sent since the last receive.
+    Empty,
+    /// The sink has fallen behind and missed the given number of values. The cursor of the sink
+    /// has been moved to the oldest value still available.
+    Lagged(u64),
+    /// The source is gone and all values have been received.
+    AllGone,
+}
+
+struct Channel<T: Send> {
+    ring: Ring<T>,
+    closed: AtomicBool,
+}
+
+
+#[test]
+fn every_sink_sees_every_value() {
+    let (mut source, mut sink1) = create::<u32>(4);
+    let mut sink2 = source.sink();
+    for value in 0..3 {
+        source.send(value);
+    }
+    for value in 0..3 {
+        assert_eq!(sink1.try_receive(), Ok(value));
+    }
+    assert_eq!(sink1.try_receive(), Err(Error::Empty));
+    for value in 0..3 {
+        assert_eq!(sink2.try_receive(), Ok(value));
+    }
+}
+
+#[test]
+fn new_sink_only_sees_future_values() {
+    let (mut source, _sink) = create::<u32>(4);
+    source.send(1);
+    let mut sink = source.sink();
+    assert_eq!(sink.try_receive(), Err(Error::Empty));
+    source.send(2);
+    assert_eq!(sink.try_receive(), Ok(2));
+}
+
+#[test]
+fn slow_sink_is_lagged() {
+    let (mut source, mut sink) = create::<u32>(2);
+    for value in 0..5 {
+        source.send(value);
+    }
+    assert_eq!(sink.try_receive(), Err(Error::Lagged(3)));
+    assert_eq!(sink.try_receive(), Ok(3));
+    assert_eq!(sink.try_receive(), Ok(4));
+}
+
+#[test]
+fn sink_receives_remaining_values_after_source_dropped() {
+    let (mut source, mut sink) = create::<u32>(2);
+    source.send(1);
+    drop(source);
+    assert!(!sink.is_connected());
+    assert_eq!(sink.receive(), Ok(1));
+    assert_eq!(sink.receive(), Err(Error::AllGone));
+}
+
+#[test]
+fn source_is_all_gone_without_sinks() {
+    let (mut source, sink) = create::<u32>(2);
+    assert_eq!(source.send(1), crate::signal::sync::State::Ready);
+    drop(sink);
+    assert_eq!(source.send(2), crate::signal::sync::State::AllGone);
+}
@@ -0,0 +1,101 @@
+use std::sync::atomic::Ordering;
+
+use crate::signal::reclaim::HazardPointer;
+use crate::signal::loom::{Arc, thread};
+use super::{Channel, Error, Source};
+
+pub struct Sink<T> where T: Clone + Send + Sync {
+    channel: Arc<Channel<T>>,
+    next: u64, // generation of the next value to receive
+}
+
+impl<T> Sink<T> where T: Clone + Send + Sync {
+    /// Creates a new Sink from the given Source, which receives all values sent from now on.
+    pub fn from(source: &Source<T>) -> Self {
+        Sink {
+            channel: source.channel.clone(),
+            next: source.channel.ring.head(),
+        }
+    }
+
+    /// Returns a copy of the next value, waiting while no new value has been sent.
+    pub fn receive(&mut self) -> Result<T, Error> {
+        let mut value = None;
+        self.wait(&mut |v| value = Some(v.clone()))?;
+        Ok(value.expect("received"))
+    }
+
+    /// Returns a copy of the next value without waiting.
+    pub fn try_receive(&mut self) -> Result<T, Error> {
+        let mut value = None;
+        self.try_process(&mut |v| value = Some(v.clone()))?;
+        Ok(value.expect("received"))
+    }
+
+    /// In contrast to [Sink::receive] this function allows the consumer to directly access the
+    /// next value via an immutable reference given by a closure. Waits while no new value has
+    /// been sent. The source is not blocked by this, even if processing takes long.
+    pub fn process(&mut self, closure: &mut dyn FnMut(&T)) -> Result<(), Error> {
+        self.wait(closure)
+    }
+
+    /// Access the next value via an immutable reference without waiting.
+    pub fn try_process(&mut self, closure: &mut dyn FnMut(&T)) -> Result<(), Error> {
+        loop {
+            let closed = self.channel.closed.load(Ordering::Acquire);
+            let ring = &self.channel.ring;
+            let head = ring.head();
+            if self.next >= head {
+                return Err(if closed { Error::AllGone } else { Error::Empty })
+            }
+            let oldest = head.saturating_sub(ring.capacity() as u64);
+            if self.next < oldest {
+                let missed = oldest - self.next;
+                self.next = oldest;
+                return Err(Error::Lagged(missed))
+            }
+            let mut guard = HazardPointer::new();
+            match ring.load(self.next, &mut guard) {
+                Some(entry) if entry.generation == self.next => {
+                    closure(&entry.value);
+                    self.next += 1;
+                    return Ok(())
+                }
+                // overwritten since head was loaded, which will be reported as lag
+                _ => continue,
+            }
+        }
+    }
+
+    /// Returns the number of values waiting to be received.
+    pub fn len(&self) -> usize {
+        let ring = &self.channel.ring;
+        (ring.head() - self.next).min(ring.capacity() as u64) as usize
+    }
+
+    /// Check if no values are waiting to be received.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check if the source still exists.
+    pub fn is_connected(&self) -> bool {
+        !self.channel.closed.load(Ordering::Acquire)
+    }
+
+    fn wait(&mut self, closure: &mut dyn FnMut(&T)) -> Result<(), Error> {
+        loop {
+            match self.try_process(closure) {
+                Err(Error::Empty) => thread::yield_now(),
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<T> Clone for Sink<T> where T: Clone + Send + Sync {
+    /// The cloned sink continues at the same position as this sink.
+    fn clone(&self) -> Self {
+        Sink { channel: self.channel.clone(), next: self.next }
+    }
+}
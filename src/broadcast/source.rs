@@ -0,0 +1,53 @@
+use std::sync::atomic::Ordering;
+
+use crate::ring::Ring;
+use crate::signal::{sync::State, loom::{Arc, atomic::AtomicBool}};
+use super::{Channel, Sink};
+
+pub struct Source<T> where T: Clone + Send + Sync {
+    pub(super) channel: Arc<Channel<T>>,
+}
+
+impl<T> Source<T> where T: Clone + Send + Sync {
+    /// Create a new source, which keeps the last `capacity` values for its sinks.
+    pub fn new(capacity: usize) -> Self {
+        Source {
+            channel: Arc::new(Channel {
+                ring: Ring::new(capacity),
+                closed: AtomicBool::new(false),
+            })
+        }
+    }
+
+    /// Send a value to all sinks. This never blocks, the oldest value is overwritten instead.
+    pub fn send(&mut self, value: T) -> State {
+        let generation = self.channel.ring.head();
+        self.channel.ring.push(generation, value);
+        match self.sink_count() {
+            0 => State::AllGone,
+            _ => State::Ready
+        }
+    }
+
+    /// Returns the number of values kept for the sinks.
+    pub fn capacity(&self) -> usize {
+        self.channel.ring.capacity()
+    }
+
+    /// Returns the number of current sinks connected to the source.
+    pub fn sink_count(&self) -> u32 {
+        // the expectation here is, that this count does not change often
+        Arc::strong_count(&self.channel) as u32 -1
+    }
+
+    /// Return a new sink, which receives all values sent from now on.
+    pub fn sink(&self) -> Sink<T> {
+        Sink::from(self)
+    }
+}
+
+impl<T> Drop for Source<T> where T: Clone + Send + Sync {
+    fn drop(&mut self) {
+        self.channel.closed.store(true, Ordering::Release)
+    }
+}
@@ -0,0 +1,131 @@
+use crate::signal::reclaim::HazardPointer;
+use crate::signal::{Signal, Heartbeat, loom::Arc};
+
+/// A shared value, which can be read and updated by any number of threads.
+/// In contrast to a signal there is no split into source and sinks, every clone of the cell is a
+/// handle to the same value. Reads are protected by hazard pointers just like the reads of a
+/// signal, writes replace the value on the heap and retire the previous one.
+/// This is useful e.g. for configuration values, which several threads may update.
+pub struct AtomicCell<T> where T: Clone + Sync + Send + Default {
+    signal: Arc<Signal<T>>,
+}
+
+impl<T> AtomicCell<T> where T: Clone + Sync + Send + Default {
+    pub fn new(value: T) -> Self {
+        AtomicCell { signal: Arc::new(Signal::boxed(value)) }
+    }
+
+    /// Returns a copy of the current value.
+    pub fn load(&self) -> T {
+        self.signal.value().0
+    }
+
+    /// Access the current value via an immutable reference given by a closure.
+    /// Writers are not blocked by this, they replace the value meanwhile.
+    pub fn process(&self, closure: &mut dyn FnMut(&T)) {
+        self.signal.process(closure);
+    }
+
+    /// Replace the current value.
+    pub fn store(&self, value: T) {
+        self.signal.replace(Box::new(value))
+    }
+
+    /// Replace the current value and return a copy of the previous one.
+    pub fn swap(&self, value: T) -> T {
+        let mut value = Box::new(value);
+        let mut guard = HazardPointer::new();
+        loop {
+            let current = self.signal.load(&mut guard);
+            let previous = current.clone();
+            match self.signal.compare_and_replace(current, value) {
+                Ok(()) => return previous,
+                Err(rejected) => value = rejected,
+            }
+        }
+    }
+
+    /// Replace the current value with the result of the closure. The closure may be called
+    /// multiple times, if the value is changed concurrently.
+    pub fn update(&self, closure: &mut dyn FnMut(&T) -> T) {
+        let mut guard = HazardPointer::new();
+        loop {
+            let current = self.signal.load(&mut guard);
+            if self.signal.compare_and_replace(current, Box::new(closure(current))).is_ok() {
+                return
+            }
+        }
+    }
+
+    /// Replace the value with `new`, if it equals `current`.
+    /// Returns false if the value did not equal `current`.
+    pub fn compare_and_set(&self, current: &T, new: T) -> bool where T: PartialEq {
+        let mut new = Box::new(new);
+        let mut guard = HazardPointer::new();
+        loop {
+            let value = self.signal.load(&mut guard);
+            if value != current {
+                return false
+            }
+            match self.signal.compare_and_replace(value, new) {
+                Ok(()) => return true,
+                Err(rejected) => new = rejected,
+            }
+        }
+    }
+
+    /// Returns the heartbeat of the cell, which tracks the updates of the value.
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.signal.heartbeat.clone()
+    }
+}
+
+impl<T> Clone for AtomicCell<T> where T: Clone + Sync + Send + Default {
+    /// Returns a new handle to the same value.
+    fn clone(&self) -> Self {
+        AtomicCell { signal: self.signal.clone() }
+    }
+}
+
+impl<T> Default for AtomicCell<T> where T: Clone + Sync + Send + Default {
+    fn default() -> Self {
+        AtomicCell::new(T::default())
+    }
+}
+
+
+#[test]
+fn load_returns_stored_value() {
+    let cell = AtomicCell::new(1);
+    assert_eq!(cell.load(), 1);
+    cell.store(2);
+    assert_eq!(cell.load(), 2);
+    let mut value = 0;
+    cell.process(&mut |v| value = *v);
+    assert_eq!(value, 2);
+}
+
+#[test]
+fn swap_returns_previous_value() {
+    let cell = AtomicCell::new(String::from("a"));
+    assert_eq!(cell.swap(String::from("b")), "a");
+    assert_eq!(cell.load(), "b");
+}
+
+#[test]
+fn compare_and_set_only_replaces_expected_value() {
+    let cell = AtomicCell::new(1);
+    assert!(!cell.compare_and_set(&2, 3));
+    assert_eq!(cell.load(), 1);
+    assert!(cell.compare_and_set(&1, 3));
+    assert_eq!(cell.load(), 3);
+}
+
+#[test]
+fn clones_share_the_value() {
+    let cell = AtomicCell::new(1);
+    let other = cell.clone();
+    other.update(&mut |v| v + 1);
+    assert_eq!(cell.load(), 2);
+    assert_eq!(cell.heartbeat().generation(), 1);
+}
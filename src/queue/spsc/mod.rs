@@ -0,0 +1,143 @@
+pub mod sink;
+pub mod source;
+
+pub use source::Source;
+pub use sink::Sink;
+
+use std::{cell::UnsafeCell, mem::MaybeUninit, sync::atomic::Ordering};
+use crate::signal::loom::{Arc, atomic::AtomicUsize};
+
+/// Create a bounded single producer single consumer queue.
+/// In contrast to a signal the queue is lossless, each value is received exactly once and in
+/// order. If the queue is full, the source has to wait for the sink or give up.
+pub fn channel<T: Send>(capacity: usize) -> (Source<T>, Sink<T>) {
+    let buffer = Arc::new(Buffer::new(capacity));
+    (Source::new(buffer.clone()), Sink::new(buffer))
+}
+
+/// Lock free ring buffer, accessed by exactly one source and one sink.
+struct Buffer<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    head: AtomicUsize, // index of the next value to pop
+    tail: AtomicUsize, // index of the next value to push
+}
+
+// Safety: - slots are only written by the single source between head and tail
+//         - slots are only read by the single sink, after the source published them with tail
+unsafe impl<T: Send> Sync for Buffer<T> {}
+
+impl<T> Buffer<T> {
+    fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "queue capacity must not be zero");
+        Buffer {
+            slots: (0..capacity).map(|_| UnsafeCell::new(MaybeUninit::uninit())).collect(),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn len(&self) -> usize {
+        self.tail.load(Ordering::Acquire).wrapping_sub(self.head.load(Ordering::Acquire))
+    }
+
+    /// Write a value to the tail. Must only be called by the source.
+    fn try_push(&self, value: T) -> Result<(), T> {
+        let tail = self.tail.load(Ordering::Relaxed);
+        if tail.wrapping_sub(self.head.load(Ordering::Acquire)) == self.capacity() {
+            return Err(value)
+        }
+        // Safety: - the slot is outside of head..tail, so the sink does not access it
+        //         - the slot is uninitialized or has been moved out by the sink
+        unsafe { (*self.slot(tail)).write(value) };
+        self.tail.store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+
+    /// Take a value from the head. Must only be called by the sink.
+    fn try_pop(&self) -> Option<T> {
+        let head = self.head.load(Ordering::Relaxed);
+        if head == self.tail.load(Ordering::Acquire) {
+            return None
+        }
+        // Safety: - the slot is inside of head..tail, so the source has initialized it and does
+        //           not access it anymore
+        //         - the value is moved out exactly once, since head is advanced afterwards
+        let value = unsafe { (*self.slot(head)).assume_init_read() };
+        self.head.store(head.wrapping_add(1), Ordering::Release);
+        Some(value)
+    }
+
+    fn slot(&self, index: usize) -> *mut MaybeUninit<T> {
+        self.slots[index % self.capacity()].get()
+    }
+}
+
+impl<T> Drop for Buffer<T> {
+    fn drop(&mut self) {
+        while self.try_pop().is_some() {}
+    }
+}
+
+
+#[test]
+fn values_are_received_in_order() {
+    let (mut source, mut sink) = channel::<u32>(4);
+    for value in 0..4 {
+        assert_eq!(source.try_push(value), Ok(crate::signal::sync::State::Ready));
+    }
+    assert_eq!(source.try_push(4), Err(4));
+    for value in 0..4 {
+        assert_eq!(sink.try_pop(), Some(value));
+    }
+    assert_eq!(sink.try_pop(), None);
+}
+
+#[test]
+fn remaining_values_are_dropped_with_the_queue() {
+    let value = std::sync::Arc::new(());
+    let (mut source, sink) = channel(4);
+    source.push(value.clone());
+    source.push(value.clone());
+    drop(source);
+    drop(sink);
+    assert_eq!(std::sync::Arc::strong_count(&value), 1);
+}
+
+#[test]
+fn source_is_all_gone_without_sink() {
+    let (mut source, sink) = channel::<u32>(1);
+    assert!(source.is_connected());
+    drop(sink);
+    assert!(!source.is_connected());
+    assert_eq!(source.push(1), crate::signal::sync::State::AllGone);
+    assert_eq!(source.push(2), crate::signal::sync::State::AllGone);
+}
+
+#[test]
+fn pop_returns_remaining_values_after_source_dropped() {
+    let (mut source, mut sink) = channel::<u32>(2);
+    source.push(1);
+    drop(source);
+    assert!(!sink.is_connected());
+    assert_eq!(sink.pop(), Some(1));
+    assert_eq!(sink.pop(), None);
+}
+
+#[test]
+fn batches_wrap_around() {
+    let (mut source, mut sink) = channel::<u32>(3);
+    assert_eq!(source.push_slice(&[1, 2]), Ok(2));
+    let mut buffer = [0; 2];
+    assert_eq!(sink.pop_into(&mut buffer), 2);
+    assert_eq!(source.push_slice(&[3, 4, 5, 6]), Ok(3));
+    assert_eq!(source.push_slice(&[6]), Ok(0));
+    let mut buffer = [0; 4];
+    assert_eq!(sink.pop_into(&mut buffer), 3);
+    assert_eq!(buffer, [3, 4, 5, 0]);
+    drop(sink);
+    assert_eq!(source.push_slice(&[6]), Err(crate::signal::sync::State::AllGone));
+}
@@ -0,0 +1,61 @@
+use crate::signal::loom::{Arc, thread};
+use super::Buffer;
+
+/// The consuming end of a queue.
+pub struct Sink<T> {
+    buffer: Arc<Buffer<T>>,
+}
+
+impl<T: Send> Sink<T> {
+    pub(super) fn new(buffer: Arc<Buffer<T>>) -> Self {
+        Sink { buffer }
+    }
+
+    /// Take the next value from the queue, waiting while the queue is empty.
+    /// Returns `None` once the queue is empty and the source is gone.
+    pub fn pop(&mut self) -> Option<T> {
+        loop {
+            // check connection before pop, a value might be pushed right before the source drops
+            let connected = self.is_connected();
+            match self.try_pop() {
+                Some(value) => return Some(value),
+                None if !connected => return None,
+                None => thread::yield_now(),
+            }
+        }
+    }
+
+    /// Take the next value from the queue without waiting.
+    pub fn try_pop(&mut self) -> Option<T> {
+        self.buffer.try_pop()
+    }
+
+    /// Move as many waiting values into the given buffer as fit, without waiting.
+    /// Returns the number of values written to the start of the buffer.
+    pub fn pop_into(&mut self, buffer: &mut [T]) -> usize {
+        let mut count = 0;
+        for slot in buffer.iter_mut() {
+            match self.buffer.try_pop() {
+                Some(value) => *slot = value,
+                None => break,
+            }
+            count += 1;
+        }
+        count
+    }
+
+    /// Returns the number of values waiting in the queue.
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Check if no values are waiting in the queue.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check if the source still exists.
+    pub fn is_connected(&self) -> bool {
+        Arc::strong_count(&self.buffer) > 1
+    }
+}
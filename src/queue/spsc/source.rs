@@ -0,0 +1,72 @@
+use crate::signal::{sync::State, loom::{Arc, thread}};
+use super::Buffer;
+
+/// The producing end of a queue.
+pub struct Source<T> {
+    buffer: Arc<Buffer<T>>,
+}
+
+impl<T: Send> Source<T> {
+    pub(super) fn new(buffer: Arc<Buffer<T>>) -> Self {
+        Source { buffer }
+    }
+
+    /// Push a value to the queue, waiting while the queue is full.
+    /// If the sink is gone the value is dropped and [State::AllGone] is returned.
+    pub fn push(&mut self, value: T) -> State {
+        let mut value = value;
+        loop {
+            match self.try_push(value) {
+                Ok(state) => return state,
+                Err(rejected) => value = rejected,
+            }
+            thread::yield_now();
+        }
+    }
+
+    /// Push a value to the queue without waiting. If the queue is full, the value is given back.
+    /// If the sink is gone the value is dropped and [State::AllGone] is returned.
+    pub fn try_push(&mut self, value: T) -> Result<State, T> {
+        if !self.is_connected() {
+            return Ok(State::AllGone)
+        }
+        self.buffer.try_push(value).map(|_| State::Ready)
+    }
+
+    /// Push as many values of the slice as fit into the queue without waiting.
+    /// Returns the number of pushed values, which is zero if the queue is full.
+    /// If the sink is gone no value is pushed and [State::AllGone] is returned.
+    pub fn push_slice(&mut self, values: &[T]) -> Result<usize, State> where T: Clone {
+        if !self.is_connected() {
+            return Err(State::AllGone)
+        }
+        Ok(values.iter()
+            .take_while(|value| self.buffer.try_push((*value).clone()).is_ok())
+            .count())
+    }
+
+    /// Returns the number of values the queue can hold.
+    pub fn capacity(&self) -> usize {
+        self.buffer.capacity()
+    }
+
+    /// Returns the number of values waiting in the queue.
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Check if no values are waiting in the queue.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of current sinks connected to the source, which is at most one.
+    pub fn sink_count(&self) -> u32 {
+        Arc::strong_count(&self.buffer) as u32 -1
+    }
+
+    /// Check if the sink still exists.
+    pub fn is_connected(&self) -> bool {
+        self.sink_count() > 0
+    }
+}
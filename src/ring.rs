@@ -0,0 +1,111 @@
+use std::sync::atomic::Ordering;
+
+use crate::signal::reclaim::{AtomicPtr, HazardPointer};
+use crate::signal::loom::atomic::AtomicU64;
+
+/// Iterator over the generation and value of the entries in a ring.
+pub type Entries<'a, T> = dyn Iterator<Item = (u64, &'a T)> + 'a;
+
+/// A value in the ring, tagged with the generation it was published with.
+pub(crate) struct Entry<T> {
+    pub generation: u64,
+    pub value: T,
+}
+
+/// A fixed size ring of hazard pointer protected entries, with a single writer.
+/// Each push allocates a new entry and retires the one it replaces, so readers can keep a
+/// reference to an entry for as long as they hold the hazard pointer, without blocking the writer.
+pub(crate) struct Ring<T: Send> {
+    slots: Box<[AtomicPtr<Entry<T>>]>,
+    head: AtomicU64, // generation following the newest entry
+}
+
+impl<T: Send + Sync> Ring<T> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "ring capacity must not be zero");
+        let slots = (0..capacity)
+            // Safety: null is a valid, empty pointer
+            .map(|_| unsafe { AtomicPtr::new(std::ptr::null_mut()) })
+            .collect();
+        Ring { slots, head: AtomicU64::new(0) }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns the generation following the newest entry.
+    pub fn head(&self) -> u64 {
+        self.head.load(Ordering::Acquire)
+    }
+
+    /// Push a new entry, replacing the entry `capacity` generations before.
+    /// Generations need to increase with each push and there must be only one writer.
+    pub fn push(&self, generation: u64, value: T) {
+        let slot = &self.slots[self.index(generation)];
+        if let Some(replaced) = slot.swap(Box::new(Entry { generation, value })) {
+            // Safety:
+            // - the entry was just replaced and can not be loaded again
+            // - there is a single writer, so it is retired only once
+            unsafe { replaced.retire() };
+        }
+        self.head.store(generation + 1, Ordering::Release)
+    }
+
+    /// Load the entry of the slot the given generation maps to. The entry belongs to a newer
+    /// generation, if the slot has been overwritten meanwhile.
+    pub fn load<'hp>(&self, generation: u64, guard: &'hp mut HazardPointer<'static>)
+        -> Option<&'hp Entry<T>>
+    {
+        self.slots[self.index(generation)].safe_load(guard)
+    }
+
+    /// Access all entries of the ring, ordered from oldest to newest generation.
+    pub fn process(&self, closure: &mut dyn FnMut(&mut Entries<'_, T>)) {
+        let mut guards: Vec<HazardPointer> = self.slots.iter().map(|_| HazardPointer::new()).collect();
+        let mut entries: Vec<&Entry<T>> = self.slots.iter()
+            .zip(guards.iter_mut())
+            .filter_map(|(slot, guard)| slot.safe_load(guard))
+            .collect();
+        entries.sort_unstable_by_key(|entry| entry.generation);
+        closure(&mut entries.iter().map(|entry| (entry.generation, &entry.value)))
+    }
+
+    fn index(&self, generation: u64) -> usize {
+        (generation % self.slots.len() as u64) as usize
+    }
+}
+
+impl<T: Send> Drop for Ring<T> {
+    fn drop(&mut self) {
+        for slot in std::mem::take(&mut self.slots).into_vec() {
+            if !slot.load_ptr().is_null() {
+                // Safety:
+                // - the ring is dropped, no entry can be loaded again
+                // - replaced entries have been retired on push, the current ones not
+                unsafe { slot.retire() };
+            }
+        }
+    }
+}
+
+
+#[test]
+fn ring_keeps_the_last_entries() {
+    let ring = Ring::new(3);
+    for generation in 0..5 {
+        ring.push(generation, generation * 10);
+    }
+    let mut entries = vec![];
+    assert_eq!(ring.head(), 5);
+    ring.process(&mut |iter| entries.extend(iter.map(|(generation, value)| (generation, *value))));
+    assert_eq!(entries, vec![(2, 20), (3, 30), (4, 40)]);
+}
+
+#[test]
+fn ring_is_empty_before_first_push() {
+    let ring = Ring::<u32>::new(2);
+    let mut count = 0;
+    ring.process(&mut |iter| count = iter.count());
+    assert_eq!(count, 0);
+}
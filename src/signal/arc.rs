@@ -0,0 +1,128 @@
+use alloc::sync::Arc;
+
+use crate::signal::sync::State;
+use super::Heartbeat;
+
+/// Create a pair of source and sink for large immutable payloads.
+/// In contrast to a signal, there are no preallocated slots which are rewritten in place. The
+/// source publishes a new `Arc` and the previous one is retired, sinks can keep a loaded `Arc` as
+/// long as they want, without creating back pressure onto the source.
+/// For types without a default value, create the source with [Source::from].
+pub fn create<T>() -> (Source<T>, Sink<T>) where T: Send + Sync + Default {
+    let source = Source::from(Arc::new(T::default()));
+    let sink = source.sink();
+    (source, sink)
+}
+
+// the value is wrapped in an option, which is default constructible for any value
+pub struct Source<T> where T: Send + Sync {
+    inner: super::mp::Source<Option<Arc<T>>>,
+}
+
+impl<T> Source<T> where T: Send + Sync {
+    /// Create a new source from a given value.
+    pub fn from(value: Arc<T>) -> Self {
+        Source { inner: super::mp::Source::from(Some(value)) }
+    }
+
+    /// Publish the value to the sinks, replacing the previous one.
+    pub fn publish(&mut self, value: Arc<T>) -> State {
+        self.inner.send(&Some(value))
+    }
+
+    /// Returns the number of current sinks connected to the source.
+    pub fn sink_count(&self) -> u32 {
+        self.inner.sink_count()
+    }
+
+    /// Return a new sink form source.
+    pub fn sink(&self) -> Sink<T> {
+        Sink { inner: self.inner.sink() }
+    }
+
+    /// Returns the heartbeat of the signal, which tracks the publishes of this source.
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.inner.heartbeat()
+    }
+}
+
+#[derive(Clone)]
+pub struct Sink<T> where T: Send + Sync {
+    inner: super::Sink<Option<Arc<T>>>,
+}
+
+impl<T> Sink<T> where T: Send + Sync {
+    /// Returns the latest published value. Only the `Arc` is cloned, not the value.
+    pub fn load(&self) -> Arc<T> {
+        // the Arc is cloned while the value is protected, so nothing is constructed
+        let mut loaded = None;
+        self.inner.process(&mut |value| loaded = value.clone());
+        loaded.expect("a source always holds a value")
+    }
+
+    /// Check if source has changed the signal, since last load.
+    pub fn changed(&self) -> bool {
+        self.inner.changed()
+    }
+
+    /// Check if sink is connected.
+    pub fn is_connected(&self) -> bool {
+        self.inner.heartbeat().is_alive()
+    }
+
+    /// Returns the heartbeat of the signal, to observe the liveness of the source.
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.inner.heartbeat()
+    }
+}
+
+
+#[test]
+fn sink_loads_published_value() {
+    let (mut source, sink) = create::<Vec<u8>>();
+    assert!(sink.load().is_empty());
+    source.publish(Arc::new(vec![1, 2, 3]));
+    assert!(sink.changed());
+    assert_eq!(*sink.load(), vec![1, 2, 3]);
+    assert!(!sink.changed());
+}
+
+#[test]
+fn loaded_value_outlives_publish() {
+    let (mut source, sink) = create::<Vec<u8>>();
+    source.publish(Arc::new(vec![1]));
+    let snapshot = sink.load();
+    source.publish(Arc::new(vec![2]));
+    assert_eq!(*snapshot, vec![1]);
+    assert_eq!(*sink.load(), vec![2]);
+}
+
+#[test]
+fn published_value_is_released_after_replacement() {
+    let (mut source, sink) = create::<Vec<u8>>();
+    let value = Arc::new(vec![1]);
+    source.publish(value.clone());
+    source.publish(Arc::new(vec![2]));
+    drop(sink);
+    drop(source);
+    super::reclaim::eager_reclaim();
+    assert_eq!(Arc::strong_count(&value), 1);
+}
+
+#[test]
+fn value_without_default_is_loaded() {
+    struct Payload(u32);
+    let mut source = Source::from(Arc::new(Payload(1)));
+    let sink = source.sink();
+    assert_eq!(sink.load().0, 1);
+    source.publish(Arc::new(Payload(2)));
+    assert_eq!(sink.load().0, 2);
+}
+
+#[test]
+fn sink_is_not_connected_after_source_dropped() {
+    let (source, sink) = create::<u32>();
+    assert!(sink.is_connected());
+    drop(source);
+    assert!(!sink.is_connected());
+}
@@ -0,0 +1,214 @@
+use core::sync::atomic::Ordering;
+#[cfg(feature = "std")]
+use std::{sync::{Mutex, MutexGuard}, time::{Duration, Instant}};
+
+use super::loom::{Arc, AtomicGeneration, Generation, atomic::{AtomicBool, AtomicU32}};
+#[cfg(feature = "std")]
+use super::loom::atomic::AtomicU64;
+
+/// Liveness information of a signal, shared between the source and everyone observing it.
+/// A heartbeat does not keep the signal itself alive and does not count as a sink, so it can be
+/// handed to a supervisor without influencing the signal (e.g. the acknowledgements of a synced
+/// signal). Without the `std` feature there is no clock, so only the generation and the liveness
+/// of the source are tracked.
+#[derive(Clone)]
+pub struct Heartbeat {
+    inner: Arc<Pulse>,
+}
+
+struct Pulse {
+    #[cfg(feature = "std")]
+    epoch: Instant,
+    #[cfg(feature = "std")]
+    published: AtomicU64, // nanoseconds since epoch
+    generation: AtomicGeneration,
+    alive: AtomicBool,
+    sinks: AtomicU32,
+    #[cfg(feature = "std")]
+    owners: Mutex<Owners>,
+    #[cfg(feature = "metrics")]
+    counters: super::stats::Counters,
+}
+
+/// Name of the signal and labels of the components owning the source and the sinks.
+#[cfg(feature = "std")]
+#[derive(Default)]
+struct Owners {
+    name: Option<String>,
+    source: Option<String>,
+    sinks: Vec<String>,
+}
+
+impl Heartbeat {
+    pub(super) fn new() -> Self {
+        Heartbeat {
+            inner: Arc::new(Pulse {
+                #[cfg(feature = "std")]
+                epoch: Instant::now(),
+                #[cfg(feature = "std")]
+                published: AtomicU64::new(0),
+                generation: AtomicGeneration::new(0),
+                alive: AtomicBool::new(true),
+                sinks: AtomicU32::new(0),
+                #[cfg(feature = "std")]
+                owners: Mutex::default(),
+                #[cfg(feature = "metrics")]
+                counters: Default::default(),
+            })
+        }
+    }
+
+    /// Record a publish of the source.
+    pub(super) fn beat(&self) {
+        #[cfg(feature = "std")]
+        self.inner.published.store(self.inner.epoch.elapsed().as_nanos() as u64, Ordering::Release);
+        self.inner.generation.fetch_add(1, Ordering::AcqRel);
+        #[cfg(feature = "metrics")]
+        self.inner.counters.publish();
+    }
+
+    /// Returns the statistics of the signal.
+    #[cfg(feature = "metrics")]
+    pub(super) fn counters(&self) -> &super::stats::Counters {
+        &self.inner.counters
+    }
+
+    /// Continue counting from the given generation, e.g. when restoring a source.
+    #[cfg(feature = "serde")]
+    #[allow(clippy::unnecessary_cast)] // truncates on targets without 64 bit atomics
+    pub(super) fn restore(&self, generation: u64) {
+        self.inner.generation.store(generation as Generation, Ordering::Release)
+    }
+
+    /// Record that the source has been dropped.
+    pub(super) fn stop(&self) {
+        self.inner.alive.store(false, Ordering::Release)
+    }
+
+    /// Record a new sink of the signal.
+    pub(super) fn connect(&self) {
+        let _sinks = self.inner.sinks.fetch_add(1, Ordering::AcqRel) + 1;
+        #[cfg(feature = "tracing")]
+        super::trace::connected(self, _sinks);
+    }
+
+    /// Record a dropped sink of the signal. A default constructed sink was never connected, so
+    /// the count does not go below zero.
+    pub(super) fn disconnect(&self) {
+        let _disconnected = self.inner.sinks.fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1));
+        #[cfg(feature = "tracing")]
+        if let Ok(sinks) = _disconnected {
+            super::trace::disconnected(self, sinks - 1);
+        }
+    }
+
+    /// Returns the generation as counted by the target, see [super::loom::AtomicGeneration].
+    pub(super) fn raw_generation(&self) -> Generation {
+        self.inner.generation.load(Ordering::Acquire)
+    }
+
+    /// Returns the generation of the signal, which is the number of publishes so far.
+    #[allow(clippy::useless_conversion)] // widens on targets without 64 bit atomics
+    pub fn generation(&self) -> u64 {
+        u64::from(self.raw_generation())
+    }
+
+    /// Check if the source of the signal still exists.
+    pub fn is_alive(&self) -> bool {
+        self.inner.alive.load(Ordering::Acquire)
+    }
+
+    /// Returns the number of sinks of the signal.
+    pub fn sink_count(&self) -> u32 {
+        self.inner.sinks.load(Ordering::Acquire)
+    }
+
+    /// Check if both heartbeats belong to the same signal.
+    pub fn is_same(&self, other: &Heartbeat) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Heartbeat {
+    /// Record the name of the signal, as registered in a [super::Registry].
+    pub(super) fn set_name(&self, name: &str) {
+        self.owners().name = Some(name.to_owned())
+    }
+
+    /// Record the owner of the source.
+    pub(super) fn label_source(&self, owner: &str) {
+        self.owners().source = Some(owner.to_owned())
+    }
+
+    /// Replace one sink owner with another, either of them may be absent.
+    pub(super) fn relabel_sink(&self, previous: Option<&str>, owner: Option<&str>) {
+        if previous.is_none() && owner.is_none() {
+            return // e.g. an unlabeled sink is dropped
+        }
+        let mut owners = self.owners();
+        if let Some(index) = previous.and_then(|previous| owners.sinks.iter().position(|o| o == previous)) {
+            owners.sinks.swap_remove(index);
+        }
+        if let Some(owner) = owner {
+            owners.sinks.push(owner.to_owned());
+        }
+    }
+
+    fn owners(&self) -> MutexGuard<'_, Owners> {
+        // the lock is only held for single assignments and operations on the vector, which leave
+        // the labels valid if they panic, at worst without the change of the panicking call, so
+        // poisoning is ignored
+        self.inner.owners.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns the point in time of the last publish, or the creation of the signal if it was
+    /// never published.
+    pub fn last(&self) -> Instant {
+        self.inner.epoch + Duration::from_nanos(self.inner.published.load(Ordering::Acquire))
+    }
+
+    /// Returns the time passed since the last publish.
+    pub fn elapsed(&self) -> Duration {
+        self.last().elapsed()
+    }
+
+    /// Returns the name of the signal, if it has been registered.
+    pub fn name(&self) -> Option<String> {
+        self.owners().name.clone()
+    }
+
+    /// Returns the owner label of the source, if it has been set.
+    pub fn source_owner(&self) -> Option<String> {
+        self.owners().source.clone()
+    }
+
+    /// Returns the owner labels of all labeled sinks, a label occurs once for each of its sinks.
+    pub fn sink_owners(&self) -> Vec<String> {
+        let mut sinks = self.owners().sinks.clone();
+        sinks.sort();
+        sinks
+    }
+}
+
+impl Default for Heartbeat {
+    /// A heartbeat without a source, as used by a default constructed sink.
+    fn default() -> Self {
+        let heartbeat = Heartbeat::new();
+        heartbeat.stop();
+        heartbeat
+    }
+}
+
+impl core::fmt::Debug for Heartbeat {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut debug = f.debug_struct("Heartbeat");
+        #[cfg(feature = "std")]
+        debug.field("elapsed", &self.elapsed());
+        debug
+            .field("generation", &self.generation())
+            .field("alive", &self.is_alive())
+            .field("sinks", &self.sink_count())
+            .finish()
+    }
+}
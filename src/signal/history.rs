@@ -0,0 +1,147 @@
+use crate::ring::Ring;
+use crate::signal::sync::State;
+use super::{Heartbeat, loom::Arc};
+
+pub use crate::ring::Entries;
+
+/// Create a pair of source and sink, which keep the last `depth` published values.
+/// In addition to the latest value, sinks can access this window of history, e.g. to filter the
+/// last samples. Each value is tagged with its generation, which counts the publishes.
+/// The history is lock free, the source is never blocked by sinks accessing it.
+pub fn create<T>(depth: usize) -> (Source<T>, Sink<T>) where T: Send + Sync + Clone + Default {
+    let source = Source::from(T::default(), depth);
+    let sink = Sink::from(&source);
+    (source, sink)
+}
+
+pub struct Source<T> where T: Clone + Sync + Send + Default {
+    inner: crate::signal::Source<T>,
+    ring: Arc<Ring<T>>,
+}
+
+impl<T> Source<T> where T: Clone + Sync + Send + Default {
+    /// Create a new source from a given value, keeping the last `depth` values.
+    pub fn from(value: T, depth: usize) -> Self {
+        let ring = Arc::new(Ring::new(depth));
+        ring.push(0, value.clone());
+        Source {
+            inner: crate::signal::Source::from(value),
+            ring,
+        }
+    }
+
+    /// Publish the given data to the sinks and append it to the history.
+    pub fn send(&mut self, data: &T) -> State {
+        let state = self.inner.send(data);
+        self.record();
+        state
+    }
+
+    /// Modify the current data with zero copy and publish the changes to the sinks.
+    /// The published value is cloned once into the history.
+    pub fn modify(&mut self, closure: &mut dyn FnMut(&mut T)) -> State {
+        let state = self.inner.modify(closure);
+        self.record();
+        state
+    }
+
+    /// Returns the number of values kept in the history.
+    pub fn depth(&self) -> usize {
+        self.ring.capacity()
+    }
+
+    /// Returns the number of current sinks connected to the source.
+    pub fn sink_count(&self) -> u32 {
+        self.inner.sink_count()
+    }
+
+    /// Return a new sink form source.
+    pub fn sink(&self) -> Sink<T> {
+        Sink::from(self)
+    }
+
+    /// Returns the heartbeat of the signal, which tracks the publishes of this source.
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.inner.heartbeat()
+    }
+
+    /// Check if the given data equals the last published data.
+    pub fn equals_last(&self, data: &T) -> bool where T: PartialEq {
+        self.inner.equals_last(data)
+    }
+
+    fn record(&mut self) {
+        let generation = self.inner.signal.heartbeat.generation();
+        self.ring.push(generation, self.inner.current().clone())
+    }
+}
+
+#[derive(Clone)]
+pub struct Sink<T> where T: Clone + Sync + Send + Default {
+    inner: crate::signal::Sink<T>,
+    ring: Arc<Ring<T>>,
+}
+
+impl<T> Sink<T> where T: Clone + Sync + Send + Default {
+    /// Creates a new Sink from the given Source.
+    pub fn from(source: &Source<T>) -> Self {
+        Sink {
+            inner: source.inner.sink(),
+            ring: source.ring.clone(),
+        }
+    }
+
+    /// Returns a copy of the latest signal value.
+    pub fn receive(&self) -> T {
+        self.inner.receive()
+    }
+
+    /// Access the latest signal value via an immutable reference given by a closure.
+    pub fn process(&self, closure: &mut dyn FnMut(&T)) {
+        self.inner.process(closure)
+    }
+
+    /// Access the history via an iterator over the generation and value of each entry, ordered
+    /// from oldest to newest. The values are not copied, they are protected from reclamation
+    /// while the closure runs. In contrast to [Sink::process] this does not block the source.
+    pub fn history(&self, closure: &mut dyn FnMut(&mut Entries<'_, T>)) {
+        self.ring.process(closure)
+    }
+
+    /// Check if source has changed the signal, since last read.
+    pub fn changed(&self) -> bool {
+        self.inner.changed()
+    }
+
+    /// Check if sink is connected.
+    pub fn is_connected(&self) -> bool {
+        self.inner.is_connected()
+    }
+
+    /// Returns the heartbeat of the signal, to observe the liveness of the source.
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.inner.heartbeat()
+    }
+}
+
+
+#[test]
+fn history_starts_with_initial_value() {
+    let (_source, sink) = create::<u32>(4);
+    let mut entries = vec![];
+    sink.history(&mut |iter| entries.extend(iter.map(|(generation, value)| (generation, *value))));
+    assert_eq!(entries, vec![(0, 0)]);
+}
+
+#[test]
+fn history_keeps_last_values_with_generation() {
+    let (mut source, sink) = create::<u32>(3);
+    for value in 1..=5 {
+        source.send(&value);
+    }
+    source.modify(&mut |value| *value = 6);
+    let mut entries = vec![];
+    sink.history(&mut |iter| entries.extend(iter.map(|(generation, value)| (generation, *value))));
+    assert_eq!(entries, vec![(4, 4), (5, 5), (6, 6)]);
+    assert_eq!(sink.receive(), 6);
+}
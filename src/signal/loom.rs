@@ -1,5 +1,44 @@
 #[cfg(loom)]
-pub use loom::{thread, sync::*};
+pub use loom::{thread, hint, cell::UnsafeCell, sync::*};
+
+#[cfg(all(not(loom), feature = "std"))]
+pub use std::{thread, sync::*};
+
+#[cfg(all(not(loom), not(feature = "std")))]
+pub use {alloc::sync::Arc, core::sync::atomic};
+
+#[cfg(not(loom))]
+pub use core::hint;
+
+/// An UnsafeCell with the interface of loom, which tracks the accesses to the value in a model.
+#[cfg(not(loom))]
+#[derive(Debug, Default)]
+pub struct UnsafeCell<T>(core::cell::UnsafeCell<T>);
 
 #[cfg(not(loom))]
-pub use std::{thread, sync::*};
\ No newline at end of file
+impl<T> UnsafeCell<T> {
+    pub const fn new(value: T) -> Self {
+        UnsafeCell(core::cell::UnsafeCell::new(value))
+    }
+
+    pub fn with<R>(&self, closure: impl FnOnce(*const T) -> R) -> R {
+        closure(self.0.get())
+    }
+
+    pub fn with_mut<R>(&self, closure: impl FnOnce(*mut T) -> R) -> R {
+        closure(self.0.get())
+    }
+}
+
+/// Atomic counter of generations, targets without 64 bit atomics count with 32 bit and wrap
+/// around after 2^32 publishes.
+#[cfg(target_has_atomic = "64")]
+pub type AtomicGeneration = atomic::AtomicU64;
+#[cfg(not(target_has_atomic = "64"))]
+pub type AtomicGeneration = atomic::AtomicU32;
+
+/// The value of an [AtomicGeneration].
+#[cfg(target_has_atomic = "64")]
+pub type Generation = u64;
+#[cfg(not(target_has_atomic = "64"))]
+pub type Generation = u32;
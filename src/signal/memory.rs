@@ -1,73 +1,124 @@
-use std::{marker::PhantomPinned, pin::Pin, ptr};
-
-use haphazard::AtomicPtr;
+use super::loom::{UnsafeCell, atomic::{AtomicUsize, Ordering::{AcqRel, Acquire, Relaxed, Release}}};
 
+/// The two slots of a single producer signal, one is read by the sinks while the source writes
+/// the other one.
+/// A reader enters the read slot by incrementing the count of readers next to the read id, so
+/// both are loaded at once. The swap takes the count of the previous read slot, before the source
+/// writes that slot it waits until the same number of readers have left it.
 pub struct Memory<T: Default> {
-    slot: [T;2],
-    read_id: usize,
-    _marker: PhantomPinned
+    slot: [UnsafeCell<T>;2],
+    state: AtomicUsize, // the read id in the lowest bit, above the readers entered since the swap
+    left: [AtomicUsize;2], // readers which have left a slot
+    entered: AtomicUsize, // readers which entered the write slot, only used by the source
 }
 
+const READ_ID: usize = 1;
+const READER: usize = 2;
+const COUNT: usize = usize::MAX / READER; // the counts wrap around alike
+
+// Safety: - a slot is only written by the single source, after all readers have left it
+//         - readers only read the read slot they have entered
+unsafe impl<T: Send + Sync + Default> Sync for Memory<T> {}
+unsafe impl<T: Send + Default> Send for Memory<T> {}
+
 impl<T> Memory<T> where T: Clone + Default {
 
-    pub fn new(value: T) -> Pin<Box<Self>> {
-        Box::pin(Memory {
-            slot: [T::default(), value],
-            read_id: 1,
-            _marker: PhantomPinned
-        })
+    pub fn new(value: T) -> Self {
+        Memory {
+            slot: [UnsafeCell::new(T::default()), UnsafeCell::new(value)],
+            state: AtomicUsize::new(1),
+            left: [AtomicUsize::new(0), AtomicUsize::new(0)],
+            entered: AtomicUsize::new(0),
+        }
     }
 
-    pub fn new_read_ptr(self: &mut Pin<Box<Self>>) -> AtomicPtr<T> {
-        unsafe{
-            // Safety:  - self is never moved here
-            //          - only some data will be written to a memory slot
-            //          - pin guaranties should not be violated by this
-            let memory = self.as_mut().get_unchecked_mut();
-            // Safety:  - self is always known to be properly initialized
-            //          - and so is the slot array
-            AtomicPtr::new(ptr::addr_of_mut!(memory.slot[memory.read_id]))
-        }
+    /// Access the current read slot, returns the id of the accessed value, which is its address.
+    pub fn process(&self, closure: &mut dyn FnMut(&T)) -> usize {
+        let id = self.state.fetch_add(READER, Acquire) & READ_ID;
+        // Safety: the source does not write a slot, before all readers entered it have left
+        let value = self.slot[id].with(|value| {
+            closure(unsafe { &*value });
+            value as usize
+        });
+        self.left[id].fetch_add(1, Release);
+        value
+    }
+
+    /// # Safety
+    /// Only a single source may write, while no reference of [Memory::current] is alive.
+    pub unsafe fn write(&self, value: &T) {
+        self.writable().with_mut(|slot| (*slot).clone_from(value))
     }
 
-    pub fn write(self: &mut Pin<Box<Self>>, value: &T) {
-        // Safety:  - self is never moved here
-        //          - only some data will be written to a memory slot
-        //          - pin guaranties should not be violated by this
-        let memory = unsafe{self.as_mut().get_unchecked_mut()};
-        memory.slot[memory.write_id()] = value.clone();
+    /// # Safety
+    /// The same as for [Memory::write].
+    pub unsafe fn write_in_place(&self, closure: &mut dyn FnMut(&mut T)) {
+        self.writable().with_mut(|slot| closure(&mut *slot))
     }
 
-    pub fn write_in_place(self: &mut Pin<Box<Self>>, closure: &mut dyn FnMut(&mut T)) {
-        // Safety:  - self is never moved here
-        //          - only some data will be written to a memory slot through a closure
-        //          - pin guaranties should not be violated by this
-        let memory = unsafe{self.as_mut().get_unchecked_mut()};
-        closure(&mut memory.slot[memory.write_id()])
+    /// Like [Memory::write_in_place], but on an error the slot is restored to the read slot.
+    /// # Safety
+    /// The same as for [Memory::write].
+    pub unsafe fn try_write_in_place<E>(&self, closure: &mut dyn FnMut(&mut T) -> Result<(), E>) -> Result<(), E> {
+        let read = &self.slot[self.read_id()];
+        self.writable().with_mut(|slot| {
+            let result = closure(&mut *slot);
+            if result.is_err() {
+                read.with(|read| (*slot).clone_from(&*read));
+            }
+            result
+        })
     }
 
-    pub fn swap(self: &mut Pin<Box<Self>>, read_ptr: &AtomicPtr<T>) {
-        // Safety:  - self is never moved here
-        //          - only some data will be written to a memory slot
-        //          - pin guaranties should not be violated by this
-        let memory = unsafe{self.as_mut().get_unchecked_mut()};
-        memory.swap_read_id();
-        // Safety:  - self is always known to be properly initialized
-        //          - and so is the slot array
-        unsafe{read_ptr.store_ptr(std::ptr::addr_of_mut!(memory.slot[memory.read_id]))};
+    /// Make the written slot the read slot.
+    /// # Safety
+    /// The same as for [Memory::write].
+    pub unsafe fn swap(&self) {
+        let state = self.state.swap(self.write_id(), AcqRel);
+        self.entered.store(state / READER, Relaxed)
+    }
+
+    /// Returns the write slot, once its last reader is gone.
+    fn writable(&self) -> &UnsafeCell<T> {
+        let id = self.write_id();
+        let entered = self.entered.load(Relaxed);
+        while self.left[id].load(Acquire) & COUNT != entered {
+            #[cfg(any(loom, feature = "std"))]
+            super::loom::thread::yield_now();
+            #[cfg(not(any(loom, feature = "std")))]
+            core::hint::spin_loop();
+        }
+        // no reader enters this slot before the next swap
+        self.left[id].store(0, Relaxed);
+        self.entered.store(0, Relaxed);
+        &self.slot[id]
+    }
+
+    fn read_id(&self) -> usize {
+        self.state.load(Acquire) & READ_ID
     }
 
     fn write_id(&self) -> usize {
-        self.read_id ^1
+        self.read_id() ^1
+    }
+
+    /// Returns the id of the current read slot, as returned by [Memory::process].
+    pub fn current_id(&self) -> usize {
+        self.slot[self.read_id()].with(|value| value as usize)
     }
 
-    fn swap_read_id(&mut self) {
-        self.read_id = self.write_id()
+    /// Returns the current read slot.
+    /// # Safety
+    /// Only the writing source may call this, the slot is written after the next swap.
+    pub unsafe fn current(&self) -> &T {
+        self.slot[self.read_id()].with(|value| &*value)
     }
 
     /// Compare the given value with the current read slot.
-    pub fn equals_current(&self, value: &T) -> bool where T: PartialEq {
-        &self.slot[self.read_id] == value
+    /// # Safety
+    /// The same as for [Memory::current].
+    pub unsafe fn equals_current(&self, value: &T) -> bool where T: PartialEq {
+        self.current() == value
     }
 
-}
\ No newline at end of file
+}
@@ -3,12 +3,48 @@ pub mod source;
 pub mod sync;
 pub mod loom;
 pub mod memory;
-pub use source::Source;
+pub mod heartbeat;
+#[cfg(feature = "std")]
+pub mod watchdog;
+#[cfg(feature = "std")]
+pub mod history;
+pub mod mp;
+pub mod arc;
+pub mod static_signal;
+#[cfg(feature = "std")]
+pub mod registry;
+#[cfg(feature = "std")]
+pub mod select;
+#[cfg(feature = "std")]
+pub mod sim;
+pub(crate) mod reclaim;
+#[cfg(all(unix, feature = "shm"))]
+pub mod shm;
+#[cfg(feature = "net")]
+pub mod net;
+#[cfg(feature = "serde")]
+pub mod snapshot;
+#[cfg(feature = "record")]
+pub mod record;
+#[cfg(feature = "metrics")]
+pub mod stats;
+#[cfg(feature = "tracing")]
+mod trace;
+pub use source::{Source, SyncFrom, Patchable};
 pub use sink::Sink;
+pub use heartbeat::Heartbeat;
+pub use static_signal::StaticSignal;
+#[cfg(feature = "std")]
+pub use registry::Registry;
+#[cfg(feature = "std")]
+pub use select::Selector;
+#[cfg(feature = "std")]
+pub use crate::select;
 
 use memory::*;
-use haphazard::{AtomicPtr, HazardPointer};
-use std::{fmt::Debug, pin::Pin};
+use reclaim::{AtomicPtr, HazardPointer};
+use alloc::boxed::Box;
+use core::fmt::Debug;
 
 
 pub fn create<T>() -> (Source<T>, Sink<T>) where T: Send + Sync + Clone + Default {
@@ -18,26 +54,83 @@ pub fn create<T>() -> (Source<T>, Sink<T>) where T: Send + Sync + Clone + Defaul
 }
 
 #[derive(Default)]
-struct Signal<T: Send + Default> {
-    ptr: Option<AtomicPtr<T>>, // Option required to retire on drop
+pub(crate) struct Signal<T: Send + Default> {
+    ptr: Option<AtomicPtr<T>>, // the value of a boxed signal, Option required to retire on drop
+    memory: Option<Memory<T>>, // the slots of a single producer signal
+    pub(crate) heartbeat: Heartbeat,
 }
 
 // impl Signal
 impl<T: Clone+Default+Send+Sync> Signal<T> {
-    fn new(pointer: AtomicPtr<T>) -> Self {
+    /// Create a signal which keeps its value in the slots of a single producer. The slots are
+    /// owned by the signal, so sinks can still read them after the source is gone.
+    fn new(value: T) -> Self {
         Signal {
-            ptr: Some(pointer)
+            ptr: None,
+            memory: Some(Memory::new(value)),
+            heartbeat: Heartbeat::new(),
         }
     }
 
-    fn swap(&self, memory: &mut Pin<Box<Memory<T>>>) {
+    /// Create a signal which owns its value on the heap. Such a signal is not bound to the memory
+    /// of a source, it is updated by replacing the value.
+    pub(crate) fn boxed(value: T) -> Self {
+        Signal {
+            ptr: Some(AtomicPtr::from(Box::new(value))),
+            memory: None,
+            heartbeat: Heartbeat::new(),
+        }
+    }
+
+    /// Returns the slots of a single producer signal.
+    fn memory(&self) -> &Memory<T> {
+        self.memory.as_ref().expect("signal of a single producer")
+    }
+
+    /// Publish the written slot of a single producer signal.
+    /// # Safety
+    /// Only the single source may publish.
+    unsafe fn swap(&self) {
+        self.memory().swap();
+        self.heartbeat.beat()
+    }
+
+    /// Replace the value of a boxed signal, the previous value is retired.
+    pub(crate) fn replace(&self, value: Box<T>) {
+        debug_assert!(self.memory.is_none(), "only boxed values can be replaced");
         if let Some(ptr) = &self.ptr {
-            // No retire needed here - we're just toggling between pre-allocated slots
-            memory.swap(ptr)
+            if let Some(replaced) = ptr.swap(value) {
+                // Safety:
+                // - the value was just replaced and can not be loaded again
+                // - only the one swapping it out retires the value
+                unsafe { replaced.retire() };
+            }
+            self.heartbeat.beat()
+        }
+    }
+
+    /// Replace the value of a boxed signal, if it is still the given one. The given reference
+    /// needs to be protected by a hazard pointer, see [Signal::load].
+    /// Returns the new value if the signal has been changed meanwhile.
+    pub(crate) fn compare_and_replace(&self, current: &T, value: Box<T>) -> Result<(), Box<T>> {
+        debug_assert!(self.memory.is_none(), "only boxed values can be replaced");
+        match &self.ptr {
+            Some(ptr) => {
+                let current = current as *const T as *mut T;
+                if let Some(replaced) = ptr.compare_exchange(current, value)? {
+                    // Safety:
+                    // - the value was just replaced and can not be loaded again
+                    // - only the one swapping it out retires the value
+                    unsafe { replaced.retire() };
+                }
+                self.heartbeat.beat();
+                Ok(())
+            }
+            None => unreachable!()
         }
     }
 
-    fn value(&self) -> (T, u64) {
+    pub(crate) fn value(&self) -> (T, usize) {
         let mut val = T::default();
         let id = self.process(&mut |value| {
             val = value.clone()
@@ -45,23 +138,23 @@ impl<T: Clone+Default+Send+Sync> Signal<T> {
         (val, id)
     }
 
-    fn process(&self, closure: &mut dyn FnMut(&T)) -> u64 {
+    /// Load the current value, protected by the given hazard pointer.
+    pub(crate) fn load<'hp>(&self, guard: &'hp mut HazardPointer<'static>) -> &'hp T {
         match &self.ptr {
-            Some(ptr) => {
-                let mut guard = HazardPointer::new();
-                let val = ptr.safe_load(&mut guard).expect("not null");
-                closure(val);
-                ptr.load_ptr() as u64
-            }
+            Some(ptr) => ptr.safe_load(guard).expect("not null"),
             None => unreachable!()
         }
     }
 
-    fn box_id(&self) -> u64 {
-        match &self.ptr {
-            Some(ptr) => ptr.load_ptr() as u64,
-            None => unreachable!(),
+    /// Access the current value, returns the id of the accessed value.
+    pub(crate) fn process(&self, closure: &mut dyn FnMut(&T)) -> usize {
+        if let Some(memory) = &self.memory {
+            return memory.process(closure)
         }
+        let mut guard = HazardPointer::new();
+        let val = self.load(&mut guard);
+        closure(val);
+        val as *const T as usize
     }
 
 }
@@ -70,9 +163,8 @@ impl<T: Clone+Default+Send+Sync> Signal<T> {
 impl<T: Send + Default> Drop for Signal<T> {
     fn drop(&mut self) {
         if let Some(ptr) = self.ptr.take() {
-            ptr.swap(Box::<T>::default());
             // Safety:
-            // - AtomicPtr has used the global domain, as required by haphazard::AtomicPtr::retire
+            // - AtomicPtr has used the global domain, as required by AtomicPtr::retire
             // - AtomicPtr is only used in signal
             unsafe{ ptr.retire() };
         }
@@ -80,16 +172,19 @@ impl<T: Send + Default> Drop for Signal<T> {
 }
 
 impl<T> Debug for Signal<T> where T: Send + Default {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Signal").field("ptr", &self.ptr).field("guard", &"invisible").finish()
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Signal")
+            .field("ptr", &self.ptr)
+            .field("guard", &"invisible")
+            .field("heartbeat", &self.heartbeat)
+            .finish()
     }
 }
 
 
 #[test]
 fn read_source_value() {
-    let mut memory = Memory::new(5);
-    let signal = Signal::new(memory.new_read_ptr());
+    let signal = Signal::new(5);
     let mut counter = 0;
     signal.process(&mut |val|{
         counter += val
@@ -99,8 +194,7 @@ fn read_source_value() {
 
 #[test]
 fn signal_does_not_panic_on_immediate_drop() {
-    let mut memory = Memory::new(false);
-    let signal = Signal::new(memory.new_read_ptr());
+    let signal = Signal::new(false);
     drop(signal);
 }
 
@@ -141,6 +235,103 @@ fn source_and_sinks_are_connected() {
     }
 }
 
+#[test]
+fn failed_modify_is_not_published() {
+    let (mut source, sink) = super::signal::create::<Vec<u32>>();
+    source.send(&vec![1]);
+    sink.receive();
+    let failed = source.try_modify(&mut |value| {
+        value.push(2);
+        Err("bailed out")
+    });
+    assert_eq!(failed, Err("bailed out"));
+    assert!(!sink.changed());
+    assert_eq!(sink.receive(), vec![1]);
+    let modified = source.try_modify(&mut |value| {
+        value.push(3);
+        Ok::<_, &str>(())
+    });
+    assert_eq!(modified, Ok(sync::State::Ready));
+    assert_eq!(sink.receive(), vec![1, 3]);
+}
+
+#[test]
+fn modify_starts_from_forwarded_value() {
+    let (mut source, sink) = super::signal::create::<u32>();
+    source.send(&1);
+    source.modify(&mut |value| *value += 1);
+    assert_eq!(sink.receive(), 1, "modifies the value published before the last one");
+    source.copy_forward();
+    for expected in 2..5 {
+        source.modify(&mut |value| *value += 1);
+        assert_eq!(sink.receive(), expected);
+    }
+    source.send(&10);
+    source.access(&mut |value| *value += 1);
+    source.modify(&mut |value| *value *= 2);
+    assert_eq!(sink.receive(), 22);
+}
+
+#[cfg(test)]
+#[derive(Default, Debug)]
+struct Table { rows: alloc::vec::Vec<u32>, clones: std::sync::Arc<core::sync::atomic::AtomicUsize> }
+
+#[cfg(test)]
+impl Clone for Table {
+    fn clone(&self) -> Self {
+        self.clones.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+        Table { rows: self.rows.clone(), clones: self.clones.clone() }
+    }
+}
+
+#[cfg(test)]
+impl Patchable for Table {
+    type Patch = (usize, u32);
+    fn apply(&mut self, (row, value): &Self::Patch) {
+        self.rows[*row] = *value
+    }
+}
+
+#[test]
+fn apply_patches_both_slots_without_clone() {
+    use core::sync::atomic::Ordering::Relaxed;
+    let table = Table { rows: alloc::vec![0; 4], ..Table::default() };
+    let clones = table.clones.clone();
+    let mut source = Source::from(table);
+    let sink = source.sink();
+    for i in 0..10 {
+        source.apply((i % 4, i as u32));
+        sink.process(&mut |table| assert_eq!(table.rows[i % 4], i as u32));
+    }
+    sink.process(&mut |table| assert_eq!(table.rows, [8, 9, 6, 7]));
+    assert_eq!(clones.load(Relaxed), 1, "only the first patch starts from a clone");
+    source.modify(&mut |table| table.rows[0] += 1);
+    sink.process(&mut |table| assert_eq!(table.rows, [9, 9, 6, 7]));
+    source.copy_forward();
+    source.access(&mut |table| table.rows[1] = 0);
+    source.apply((2, 0));
+    source.apply((3, 0));
+    sink.process(&mut |table| assert_eq!(table.rows, [9, 0, 0, 0]));
+    let mut source = Source::from(Table { rows: alloc::vec![0; 2], ..Table::default() });
+    let sink = source.sink();
+    source.send(&Table { rows: alloc::vec![5, 5], ..Table::default() });
+    source.access(&mut |_| {});
+    source.apply((0, 1));
+    sink.process(&mut |table| assert_eq!(table.rows, [1, 5], "the patch goes onto the last send"));
+}
+
+#[test]
+fn modify_after_apply_starts_from_patched_value() {
+    let mut source = Source::from(Table { rows: alloc::vec![0; 2], ..Table::default() });
+    let sink = source.sink();
+    source.apply((0, 1));
+    source.apply((1, 2));
+    source.modify(&mut |table| table.rows[0] += 10);
+    assert_eq!(sink.receive().rows, [11, 2], "without copy_forward");
+    source.modify(&mut |table| table.rows[1] += 10);
+    assert_eq!(sink.receive().rows, [1, 12], "the slot before the modify again");
+}
+
 #[test]
 #[ignore = "only show sizes"]
 fn sizes() {
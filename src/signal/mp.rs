@@ -0,0 +1,189 @@
+use alloc::boxed::Box;
+use core::sync::atomic::Ordering;
+
+use super::reclaim::HazardPointer;
+use crate::signal::sync::State;
+use super::{Signal, Sink, Heartbeat, loom::{Arc, atomic::AtomicU32}};
+
+/// Merges the current value of the signal with the sent one into the value to publish.
+pub type Merge<T> = dyn Fn(&T, &T) -> T + Send + Sync;
+
+/// Create a pair of source and sink, where the source can be cloned to publish from multiple
+/// threads (multiple producer multiple consumer). The last sent value wins.
+/// Sinks are the same as for a single producer signal.
+pub fn create<T>() -> (Source<T>, Sink<T>) where T: Send + Sync + Clone + Default {
+    let source = Source::from(T::default());
+    let sink = source.sink();
+    (source, sink)
+}
+
+/// Like [create], but each sent value is merged with the current one by the given closure.
+/// The closure is called with the current and the sent value.
+pub fn create_with<T>(merge: impl Fn(&T, &T) -> T + Send + Sync + 'static) -> (Source<T>, Sink<T>)
+    where T: Send + Sync + Clone + Default
+{
+    let source = Source::with_merge(T::default(), merge);
+    let sink = source.sink();
+    (source, sink)
+}
+
+/// A source which can be cloned. In contrast to the single producer source, the value is not
+/// written in place, each publish allocates a new value and retires the previous one.
+/// Concurrent publishes are linearized by a compare and swap of the value.
+pub struct Source<T> where T: Clone + Sync + Send + Default {
+    signal: Arc<Signal<T>>,
+    sources: Arc<AtomicU32>,
+    merge: Option<alloc::sync::Arc<Merge<T>>>, // not shared between threads of a loom model
+}
+
+impl<T> Source<T> where T: Clone + Sync + Send + Default {
+    /// Create a new source from a given value, the last sent value wins.
+    pub fn from(value: T) -> Self {
+        Source {
+            signal: Arc::new(Signal::boxed(value)),
+            sources: Arc::new(AtomicU32::new(1)),
+            merge: None,
+        }
+    }
+
+    /// Create a new source from a given value, each sent value is merged with the current one.
+    pub fn with_merge(value: T, merge: impl Fn(&T, &T) -> T + Send + Sync + 'static) -> Self {
+        Source {
+            signal: Arc::new(Signal::boxed(value)),
+            sources: Arc::new(AtomicU32::new(1)),
+            merge: Some(alloc::sync::Arc::new(merge)),
+        }
+    }
+
+    /// Publish the data to the sinks, merged with the current value if a merge is set.
+    pub fn send(&mut self, data: &T) -> State {
+        match &self.merge {
+            Some(merge) => self.update(&mut |current| merge(current, data)),
+            None => self.signal.replace(Box::new(data.clone())),
+        }
+        self.state()
+    }
+
+    /// Modify a copy of the current value and publish it to the sinks.
+    /// The closure may be called multiple times, if another source publishes concurrently.
+    pub fn modify(&mut self, closure: &mut dyn FnMut(&mut T)) -> State {
+        self.update(&mut |current| {
+            let mut value = current.clone();
+            closure(&mut value);
+            value
+        });
+        self.state()
+    }
+
+    /// Returns the number of current sinks connected to the source.
+    pub fn sink_count(&self) -> u32 {
+        // the expectation here is, that this count does not change often
+        (Arc::strong_count(&self.signal) as u32).saturating_sub(self.sources.load(Ordering::Acquire))
+    }
+
+    /// Return a new sink form source.
+    pub fn sink(&self) -> Sink<T> {
+        Sink::new(self.signal.clone())
+    }
+
+    /// Returns the heartbeat of the signal, which tracks the publishes of all sources.
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.signal.heartbeat.clone()
+    }
+
+    /// Check if the given data equals the last published data.
+    pub fn equals_last(&self, data: &T) -> bool where T: PartialEq {
+        let mut equal = false;
+        self.signal.process(&mut |value| equal = value == data);
+        equal
+    }
+
+    fn update(&self, closure: &mut dyn FnMut(&T) -> T) {
+        let mut guard = HazardPointer::new();
+        loop {
+            let current = self.signal.load(&mut guard);
+            if self.signal.compare_and_replace(current, Box::new(closure(current))).is_ok() {
+                return
+            }
+        }
+    }
+
+    fn state(&self) -> State {
+        match self.sink_count() {
+            0 => State::AllGone,
+            _ => State::Ready
+        }
+    }
+}
+
+impl<T> Clone for Source<T> where T: Clone + Sync + Send + Default {
+    /// Returns a new source of the same signal.
+    fn clone(&self) -> Self {
+        self.sources.fetch_add(1, Ordering::AcqRel);
+        Source {
+            signal: self.signal.clone(),
+            sources: self.sources.clone(),
+            merge: self.merge.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Source<T> where T: Clone + Sync + Send + Default {
+    fn drop(&mut self) {
+        if self.sources.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.signal.heartbeat.stop()
+        }
+    }
+}
+
+
+#[test]
+fn last_sent_value_wins() {
+    let (mut source1, sink) = create::<u32>();
+    let mut source2 = source1.clone();
+    source1.send(&1);
+    source2.send(&2);
+    assert!(sink.changed());
+    assert_eq!(sink.receive(), 2);
+    assert!(!sink.changed());
+    assert_eq!(source1.sink_count(), 1);
+}
+
+#[test]
+fn sent_values_are_merged() {
+    let (mut source1, sink) = create_with::<u32>(|current, sent| current.max(sent).to_owned());
+    let mut source2 = source1.clone();
+    source1.send(&5);
+    source2.send(&3);
+    assert_eq!(sink.receive(), 5);
+}
+
+#[test]
+fn modify_starts_from_current_value() {
+    let (mut source1, sink) = create::<u32>();
+    let mut source2 = source1.clone();
+    source1.modify(&mut |value| *value += 1);
+    source2.modify(&mut |value| *value += 1);
+    assert_eq!(sink.receive(), 2);
+    assert!(source1.equals_last(&2));
+}
+
+#[test]
+fn heartbeat_stops_with_last_source() {
+    let (source1, sink) = create::<u32>();
+    let source2 = source1.clone();
+    drop(source1);
+    assert!(sink.heartbeat().is_alive());
+    drop(source2);
+    assert!(!sink.heartbeat().is_alive());
+}
+
+#[test]
+fn all_gone_without_sinks() {
+    let (mut source, sink) = create::<u32>();
+    let other = source.clone();
+    assert_eq!(source.send(&1), State::Ready);
+    drop(sink);
+    assert_eq!(source.send(&2), State::AllGone);
+    drop(other);
+}
@@ -0,0 +1,403 @@
+use std::{
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{Arc, atomic::{AtomicBool, Ordering}},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+#[cfg(unix)]
+use std::{os::unix::net::{UnixListener, UnixStream}, path::{Path, PathBuf}};
+
+use serde::{Serialize, de::DeserializeOwned};
+use super::{Source, Sink};
+
+const MAGIC: [u8; 4] = *b"ATMX";
+const VERSION: u16 = 1;
+const POLL: Duration = Duration::from_millis(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(1);
+const IDLE: Duration = Duration::from_millis(100); // how often a quiet client checks its sinks
+const TIMEOUT: Duration = Duration::from_secs(5); // to connect and to receive the handshake
+
+/// The largest value a client accepts by default, see [connect_with].
+pub const MAX_FRAME: usize = 64 << 20;
+
+/// Mirror the signal of the given sink to every client connecting to the TCP address.
+/// Only the latest value is replicated. If a client can not keep up, intermediate values are
+/// skipped, the same way a local sink would skip them.
+/// The server stops when the returned handle is dropped or the source of the sink is gone.
+pub fn serve<T>(sink: Sink<T>, addr: impl ToSocketAddrs) -> io::Result<Server>
+    where T: Serialize + Clone + Default + Send + Sync + 'static
+{
+    let listener = TcpListener::bind(addr)?;
+    let local = listener.local_addr()?;
+    listener.set_nonblocking(true)?;
+    Ok(Server::spawn(sink, Listener::Tcp(listener), Some(local)))
+}
+
+/// Like [serve], but listening on a Unix domain socket at the given path.
+#[cfg(unix)]
+pub fn serve_unix<T>(sink: Sink<T>, path: impl AsRef<Path>) -> io::Result<Server>
+    where T: Serialize + Clone + Default + Send + Sync + 'static
+{
+    let listener = UnixListener::bind(path.as_ref())?;
+    listener.set_nonblocking(true)?;
+    Ok(Server::spawn(sink, Listener::Unix(listener, path.as_ref().to_owned()), None))
+}
+
+/// Connect to a signal served at the TCP address and return a local sink of it.
+/// The first connection is established before returning, it fails with
+/// [io::ErrorKind::TimedOut] if the server does not complete the handshake in time. Afterwards a
+/// background thread replicates the values and reconnects if the connection is lost. The sink
+/// keeps the last received value meanwhile. The thread ends once all sinks of the local signal
+/// are dropped. Values larger than [MAX_FRAME] are rejected by dropping the connection.
+pub fn connect<T>(addr: impl ToSocketAddrs) -> io::Result<Sink<T>>
+    where T: DeserializeOwned + Clone + Default + Send + Sync + 'static
+{
+    connect_with(addr, MAX_FRAME)
+}
+
+/// Like [connect], but accepting values up to the given number of bytes.
+pub fn connect_with<T>(addr: impl ToSocketAddrs, max_frame: usize) -> io::Result<Sink<T>>
+    where T: DeserializeOwned + Clone + Default + Send + Sync + 'static
+{
+    mirror(Address::Tcp(addr.to_socket_addrs()?.collect()), max_frame)
+}
+
+/// Like [connect], but connecting to a Unix domain socket at the given path.
+#[cfg(unix)]
+pub fn connect_unix<T>(path: impl AsRef<Path>) -> io::Result<Sink<T>>
+    where T: DeserializeOwned + Clone + Default + Send + Sync + 'static
+{
+    connect_unix_with(path, MAX_FRAME)
+}
+
+/// Like [connect_unix], but accepting values up to the given number of bytes.
+#[cfg(unix)]
+pub fn connect_unix_with<T>(path: impl AsRef<Path>, max_frame: usize) -> io::Result<Sink<T>>
+    where T: DeserializeOwned + Clone + Default + Send + Sync + 'static
+{
+    mirror(Address::Unix(path.as_ref().to_owned()), max_frame)
+}
+
+trait Stream: Read + Write + Send {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+}
+
+impl Stream for TcpStream {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_read_timeout(self, timeout)
+    }
+}
+
+#[cfg(unix)]
+impl Stream for UnixStream {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        UnixStream::set_read_timeout(self, timeout)
+    }
+}
+
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener, PathBuf),
+}
+
+impl Listener {
+    fn accept(&self) -> io::Result<Box<dyn Stream>> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, _) = listener.accept()?;
+                stream.set_nonblocking(false)?;
+                stream.set_nodelay(true)?;
+                Ok(Box::new(stream))
+            }
+            #[cfg(unix)]
+            Listener::Unix(listener, _) => {
+                let (stream, _) = listener.accept()?;
+                stream.set_nonblocking(false)?;
+                Ok(Box::new(stream))
+            }
+        }
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        match self {
+            #[cfg(unix)]
+            Listener::Unix(_, path) => { let _ = std::fs::remove_file(path); }
+            _ => {}
+        }
+    }
+}
+
+/// Handle of a running server, the server is stopped when it is dropped.
+pub struct Server {
+    local: Option<SocketAddr>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Server {
+    fn spawn<T>(sink: Sink<T>, listener: Listener, local: Option<SocketAddr>) -> Self
+        where T: Serialize + Clone + Default + Send + Sync + 'static
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let stop = stop.clone();
+            thread::spawn(move || {
+                let heartbeat = sink.heartbeat();
+                while !stop.load(Ordering::Acquire) && heartbeat.is_alive() {
+                    match listener.accept() {
+                        Ok(stream) => {
+                            let sink = sink.clone();
+                            let stop = stop.clone();
+                            thread::spawn(move || {
+                                // the client is gone if this fails, nothing left to do
+                                let _ = replicate(sink, stream, &stop);
+                            });
+                        }
+                        Err(_) => thread::sleep(POLL),
+                    }
+                }
+            })
+        };
+        Server { local, stop, thread: Some(thread) }
+    }
+
+    /// Returns the address of a TCP server, e.g. to get the port after binding to port 0.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Send the latest value to the client, whenever it changes.
+fn replicate<T>(sink: Sink<T>, mut stream: Box<dyn Stream>, stop: &AtomicBool) -> io::Result<()>
+    where T: Serialize + Clone + Default + Send + Sync
+{
+    let heartbeat = sink.heartbeat();
+    stream.write_all(&MAGIC)?;
+    stream.write_all(&VERSION.to_le_bytes())?;
+    while !stop.load(Ordering::Acquire) {
+        if sink.changed() {
+            let value = sink.receive();
+            let payload = bincode::serialize(&value)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            stream.write_all(&(payload.len() as u32).to_le_bytes())?;
+            stream.write_all(&payload)?;
+            stream.flush()?;
+        } else if !heartbeat.is_alive() {
+            break
+        } else {
+            thread::sleep(POLL);
+        }
+    }
+    Ok(())
+}
+
+enum Address {
+    Tcp(Vec<SocketAddr>),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl Address {
+    /// Connect and check the handshake, as long as the client is alive.
+    fn connect(&self, alive: &dyn Fn() -> bool) -> io::Result<Box<dyn Stream>> {
+        let mut stream: Box<dyn Stream> = match self {
+            Address::Tcp(addrs) => {
+                let stream = connect_tcp(addrs)?;
+                stream.set_nodelay(true)?;
+                Box::new(stream)
+            }
+            #[cfg(unix)]
+            Address::Unix(path) => Box::new(UnixStream::connect(path)?),
+        };
+        stream.set_read_timeout(Some(IDLE))?;
+        let mut handshake = [0; 6];
+        read_exact(&mut *stream, &mut handshake, alive)?;
+        if handshake[..4] != MAGIC || handshake[4..] != VERSION.to_le_bytes() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "unsupported protocol"))
+        }
+        Ok(stream)
+    }
+}
+
+/// Connect to the first of the addresses which accepts within the timeout.
+fn connect_tcp(addrs: &[SocketAddr]) -> io::Result<TcpStream> {
+    let mut error = io::Error::new(io::ErrorKind::InvalidInput, "no address to connect to");
+    for addr in addrs {
+        match TcpStream::connect_timeout(addr, TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => error = e,
+        }
+    }
+    Err(error)
+}
+
+/// Publish the values received from the address to a local signal, reconnecting as needed.
+fn mirror<T>(address: Address, max_frame: usize) -> io::Result<Sink<T>>
+    where T: DeserializeOwned + Clone + Default + Send + Sync + 'static
+{
+    let deadline = Instant::now() + TIMEOUT;
+    let stream = address.connect(&|| Instant::now() < deadline).map_err(|e| match e.kind() {
+        io::ErrorKind::NotConnected => io::Error::new(io::ErrorKind::TimedOut, "no handshake from the server"),
+        _ => e,
+    })?;
+    let mut source = Source::from(T::default());
+    let sink = source.sink();
+    thread::spawn(move || {
+        let mut stream = Some(stream);
+        let mut backoff = POLL;
+        while source.sink_count() > 0 {
+            let connected = stream.take().map_or_else(|| address.connect(&|| source.sink_count() > 0), Ok);
+            match connected {
+                Ok(connected) => {
+                    backoff = POLL;
+                    // the connection is lost if this fails, reconnect
+                    let _ = receive(connected, &mut source, max_frame);
+                }
+                Err(_) => {
+                    thread::sleep(backoff);
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+            }
+        }
+    });
+    Ok(sink)
+}
+
+fn receive<T>(mut stream: Box<dyn Stream>, source: &mut Source<T>, max_frame: usize) -> io::Result<()>
+    where T: DeserializeOwned + Clone + Default + Send + Sync
+{
+    let mut header = [0; 4];
+    let mut payload = Vec::new();
+    loop {
+        let alive = || source.sink_count() > 0;
+        read_exact(&mut *stream, &mut header, &alive)?;
+        let len = u32::from_le_bytes(header) as usize;
+        if len > max_frame {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "frame exceeds the maximum size"))
+        }
+        payload.resize(len, 0);
+        read_exact(&mut *stream, &mut payload, &alive)?;
+        let value: T = bincode::deserialize(&payload)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        source.send(&value);
+    }
+}
+
+/// Fill the buffer from a stream with a read timeout. On a quiet connection it keeps waiting as
+/// long as the client is alive, otherwise it fails with [io::ErrorKind::NotConnected].
+fn read_exact(stream: &mut dyn Stream, mut buf: &mut [u8], alive: &dyn Fn() -> bool) -> io::Result<()> {
+    while !buf.is_empty() {
+        match stream.read(buf) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(read) => buf = &mut buf[read..],
+            Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {
+                if !alive() {
+                    return Err(io::ErrorKind::NotConnected.into())
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+fn wait_for<T: Clone + Default + Send + Sync + PartialEq>(sink: &Sink<T>, value: T) {
+    let start = std::time::Instant::now();
+    while sink.receive() != value {
+        assert!(start.elapsed() < Duration::from_secs(10), "value not replicated");
+        thread::sleep(POLL);
+    }
+}
+
+#[test]
+fn signal_is_replicated_over_tcp() {
+    let (mut source, sink) = super::create::<String>();
+    source.send(&String::from("first"));
+    let server = serve(sink, "127.0.0.1:0").expect("server");
+    let remote = connect::<String>(server.local_addr().expect("tcp")).expect("connected");
+    wait_for(&remote, String::from("first"));
+    source.send(&String::from("second"));
+    wait_for(&remote, String::from("second"));
+}
+
+#[cfg(unix)]
+#[test]
+fn signal_is_replicated_over_unix_socket_after_reconnect() {
+    let path = std::env::temp_dir().join(format!("atomx-net-{}.sock", std::process::id()));
+    let (mut source, sink) = super::create::<u32>();
+    source.send(&1);
+    let server = serve_unix(sink.clone(), &path).expect("server");
+    let remote = connect_unix::<u32>(&path).expect("connected");
+    wait_for(&remote, 1);
+
+    drop(server);
+    source.send(&2);
+    let _server = serve_unix(sink, &path).expect("server");
+    wait_for(&remote, 2);
+}
+
+#[cfg(test)]
+fn fake_server(frame: Option<&[u8]>) -> (SocketAddr, std::sync::mpsc::Receiver<io::Result<usize>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("listener");
+    let addr = listener.local_addr().expect("addr");
+    let frame = frame.map(<[u8]>::to_vec);
+    let (closed, receiver) = std::sync::mpsc::channel();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().expect("client");
+        // without a frame the server stays silent, not even sending the handshake
+        if let Some(frame) = frame {
+            stream.write_all(&MAGIC).expect("handshake");
+            stream.write_all(&VERSION.to_le_bytes()).expect("handshake");
+            stream.write_all(&frame).expect("frame");
+        }
+        // reports 0 read bytes once the client closes the connection
+        stream.set_read_timeout(Some(Duration::from_secs(10))).expect("timeout");
+        let _ = closed.send(stream.read(&mut [0; 1]));
+    });
+    (addr, receiver)
+}
+
+#[test]
+fn oversized_frame_is_rejected() {
+    let (addr, closed) = fake_server(Some(&u32::MAX.to_le_bytes()));
+    let remote = connect_with::<Vec<u8>>(addr, 1024).expect("connected");
+    assert_eq!(closed.recv().expect("server").expect("closed, not timed out"), 0);
+    assert!(remote.receive().is_empty());
+}
+
+#[test]
+fn client_closes_quiet_connection_without_sinks() {
+    let value = bincode::serialize(&7u32).expect("serialized");
+    let frame = [&(value.len() as u32).to_le_bytes()[..], &value].concat();
+    let (addr, closed) = fake_server(Some(&frame));
+    let remote = connect::<u32>(addr).expect("connected");
+    // the client waits for the next frame on the quiet connection
+    wait_for(&remote, 7);
+    drop(remote);
+    assert_eq!(closed.recv().expect("server").expect("closed, not timed out"), 0);
+}
+
+#[test]
+fn connect_times_out_without_handshake() {
+    let (addr, _closed) = fake_server(None);
+    let start = Instant::now();
+    let error = connect::<u32>(addr).err().expect("no handshake");
+    assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+    assert!(start.elapsed() < TIMEOUT * 2);
+}
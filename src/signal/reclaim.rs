@@ -0,0 +1,206 @@
+//! Reclamation of values which are replaced while sinks may still read them.
+//!
+//! With the `hazard` feature (default) the hazard pointers of haphazard are used. Without it, a
+//! simpler strategy for targets where haphazard is not available counts the active readers and
+//! frees retired values once no reader is active anymore. It only needs atomic pointers and a
+//! counter, but retired values pile up as long as reads overlap without a pause.
+//! Both provide the same interface, the one of haphazard.
+//! Under loom the counted strategy is always used, built on the atomics of loom, so the orderings
+//! of the pointer accesses are explored by the models.
+
+#[cfg(all(feature = "hazard", not(loom)))]
+pub(crate) use haphazard::{AtomicPtr, HazardPointer};
+
+/// Reclaim all retired values which are not protected anymore.
+#[cfg(all(feature = "hazard", not(loom)))]
+#[allow(dead_code)]
+pub(crate) fn eager_reclaim() {
+    haphazard::Domain::global().eager_reclaim();
+}
+
+#[cfg(any(not(feature = "hazard"), loom))]
+#[allow(unused_imports)]
+pub(crate) use counted::{AtomicPtr, HazardPointer, eager_reclaim};
+
+#[cfg(any(not(feature = "hazard"), loom))]
+mod counted {
+    use alloc::{boxed::Box, vec::Vec};
+    use core::{cell::UnsafeCell, marker::PhantomData, mem};
+    use crate::signal::loom::{hint, atomic::{self, AtomicBool, AtomicUsize, Ordering::{Acquire, Release, SeqCst}}};
+
+    /// Number of active guards in the process.
+    #[cfg(not(loom))]
+    static READERS: AtomicUsize = AtomicUsize::new(0);
+    #[cfg(not(loom))]
+    static RETIRED: Retired = Retired { locked: AtomicBool::new(false), values: UnsafeCell::new(Vec::new()) };
+
+    // the atomics of loom are not const and need to be reset for every execution of a model
+    #[cfg(loom)]
+    loom::lazy_static! {
+        static ref READERS: AtomicUsize = AtomicUsize::new(0);
+        static ref RETIRED: Retired = Retired { locked: AtomicBool::new(false), values: UnsafeCell::new(Vec::new()) };
+    }
+
+    struct Retired {
+        locked: AtomicBool,
+        values: UnsafeCell<Vec<Value>>,
+    }
+
+    // Safety: the values are only accessed while the spin lock is held
+    unsafe impl Sync for Retired {}
+
+    impl Retired {
+        fn with(&self, closure: impl FnOnce(&mut Vec<Value>)) {
+            while self.locked.compare_exchange_weak(false, true, Acquire, Acquire).is_err() {
+                hint::spin_loop();
+            }
+            // Safety: the lock is held
+            closure(unsafe { &mut *self.values.get() });
+            self.locked.store(false, Release);
+        }
+    }
+
+    /// A retired value and the function to drop it.
+    struct Value {
+        ptr: *mut (),
+        drop: unsafe fn(*mut ()),
+    }
+
+    unsafe fn drop_box<T>(ptr: *mut ()) {
+        drop(Box::from_raw(ptr as *mut T))
+    }
+
+    fn retire<T>(ptr: *mut T) {
+        RETIRED.with(|values| values.push(Value { ptr: ptr as *mut (), drop: drop_box::<T> }));
+        if READERS.load(SeqCst) == 0 {
+            eager_reclaim()
+        }
+    }
+
+    /// Reclaim all retired values, if no reader is active.
+    /// Every value taken here has been replaced before, so a reader which still has access to it
+    /// has been counted before the check below. A reader counted afterwards only sees new values.
+    pub(crate) fn eager_reclaim() {
+        let mut batch = Vec::new();
+        RETIRED.with(|values| mem::swap(values, &mut batch));
+        match READERS.load(SeqCst) {
+            // Safety: the values are not reachable anymore and no reader holds one of them
+            0 => batch.into_iter().for_each(|value| unsafe { (value.drop)(value.ptr) }),
+            _ => RETIRED.with(|values| values.append(&mut batch)),
+        }
+    }
+
+    /// Protects every value loaded through it, until it is dropped.
+    pub struct HazardPointer<'domain> {
+        active: bool,
+        _domain: PhantomData<&'domain ()>,
+    }
+
+    impl HazardPointer<'static> {
+        pub fn new() -> Self {
+            HazardPointer { active: false, _domain: PhantomData }
+        }
+    }
+
+    impl Drop for HazardPointer<'_> {
+        fn drop(&mut self) {
+            if self.active && READERS.fetch_sub(1, SeqCst) == 1 {
+                eager_reclaim()
+            }
+        }
+    }
+
+    pub struct AtomicPtr<T>(atomic::AtomicPtr<T>);
+
+    /// A value which has been replaced in an [AtomicPtr].
+    pub struct Replaced<T>(*mut T);
+
+    impl<T> Replaced<T> {
+        /// # Safety
+        /// The value must not be reachable anymore and only be retired once.
+        pub unsafe fn retire(self) {
+            retire(self.0)
+        }
+    }
+
+    impl<T> AtomicPtr<T> {
+        /// # Safety
+        /// The pointer must be valid while it is stored, and boxed if it is ever retired.
+        #[cfg(feature = "std")] // only used by the ring
+        pub unsafe fn new(ptr: *mut T) -> Self {
+            AtomicPtr(atomic::AtomicPtr::new(ptr))
+        }
+
+        pub fn load_ptr(&self) -> *mut T {
+            self.0.load(SeqCst)
+        }
+
+        pub fn safe_load<'hp>(&self, guard: &'hp mut HazardPointer<'_>) -> Option<&'hp T> {
+            if !guard.active {
+                READERS.fetch_add(1, SeqCst);
+                guard.active = true;
+            }
+            // Safety: the value is not reclaimed while a guard is active
+            unsafe { self.0.load(SeqCst).as_ref() }
+        }
+
+        pub fn swap(&self, value: Box<T>) -> Option<Replaced<T>> {
+            let replaced = self.0.swap(Box::into_raw(value), SeqCst);
+            (!replaced.is_null()).then_some(Replaced(replaced))
+        }
+
+        pub fn compare_exchange(&self, current: *mut T, value: Box<T>) -> Result<Option<Replaced<T>>, Box<T>> {
+            let value = Box::into_raw(value);
+            match self.0.compare_exchange(current, value, SeqCst, SeqCst) {
+                Ok(replaced) => Ok((!replaced.is_null()).then_some(Replaced(replaced))),
+                // Safety: the value has just been created from a box and was not stored
+                Err(_) => Err(unsafe { Box::from_raw(value) }),
+            }
+        }
+
+        /// Retire the current value.
+        /// # Safety
+        /// The current value must be boxed and only be retired once.
+        pub unsafe fn retire(self) {
+            let ptr = self.0.load(SeqCst);
+            if !ptr.is_null() {
+                retire(ptr)
+            }
+        }
+    }
+
+    impl<T> From<Box<T>> for AtomicPtr<T> {
+        fn from(value: Box<T>) -> Self {
+            AtomicPtr(atomic::AtomicPtr::new(Box::into_raw(value)))
+        }
+    }
+
+    impl<T> core::fmt::Debug for AtomicPtr<T> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_tuple("AtomicPtr").field(&self.load_ptr()).finish()
+        }
+    }
+
+
+    #[test]
+    fn retired_value_is_dropped_after_last_reader() {
+        use alloc::sync::Arc;
+        let value = Arc::new(1);
+        let ptr = AtomicPtr::from(Box::new(value.clone()));
+        let mut guard = HazardPointer::new();
+        assert_eq!(**ptr.safe_load(&mut guard).expect("not null"), 1);
+        unsafe { ptr.swap(Box::new(Arc::new(2))).expect("replaced").retire() };
+        assert_eq!(Arc::strong_count(&value), 2);
+        drop(guard);
+        // readers of other tests may still be active
+        for _ in 0..1000 {
+            eager_reclaim();
+            if Arc::strong_count(&value) == 1 {
+                break
+            }
+            std::thread::yield_now();
+        }
+        assert_eq!(Arc::strong_count(&value), 1);
+        unsafe { ptr.retire() };
+    }
+}
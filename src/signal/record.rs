@@ -0,0 +1,294 @@
+use std::{
+    convert::TryFrom,
+    io::{self, Read, Write},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::{Serialize, de::DeserializeOwned};
+use crate::signal::sync::State;
+use super::{Source, Sink};
+
+const MAGIC: [u8; 7] = *b"ATMXREC";
+const VERSION: u8 = 1;
+
+/// Records the publishes of signals into a compact binary log.
+///
+/// The log starts with a header of magic and version. Each record consists of the time since the
+/// previous record in nanoseconds, the channel, the generation and the length of the value, all
+/// as LEB128 variable length integers, followed by the value serialized with bincode.
+/// Channels are chosen by the user to distinguish multiple signals in the same log.
+pub struct Recorder<W: Write> {
+    writer: W,
+    epoch: Instant,
+    last: u64, // nanoseconds since epoch of the last record
+    payload: Vec<u8>,
+}
+
+impl<W: Write> Recorder<W> {
+    /// Create a new recorder writing to the given writer, the header is written immediately.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        Ok(Recorder { writer, epoch: Instant::now(), last: 0, payload: Vec::new() })
+    }
+
+    /// Record a value with its generation.
+    pub fn record<T: Serialize>(&mut self, channel: u16, generation: u64, value: &T) -> io::Result<()> {
+        self.payload.clear();
+        bincode::serialize_into(&mut self.payload, value).map_err(invalid_data)?;
+        let now = self.epoch.elapsed().as_nanos() as u64;
+        write_varint(&mut self.writer, now - self.last)?;
+        write_varint(&mut self.writer, channel as u64)?;
+        write_varint(&mut self.writer, generation)?;
+        write_varint(&mut self.writer, self.payload.len() as u64)?;
+        self.writer.write_all(&self.payload)?;
+        self.last = now;
+        Ok(())
+    }
+
+    /// Publish the data through the source and record it.
+    pub fn send<T>(&mut self, channel: u16, source: &mut Source<T>, data: &T) -> io::Result<State>
+        where T: Serialize + Clone + Sync + Send + Default
+    {
+        let state = source.send(data);
+        self.record(channel, source.signal.heartbeat.generation(), data)?;
+        Ok(state)
+    }
+
+    /// Modify the data of the source with zero copy, publish and record it.
+    pub fn modify<T>(&mut self, channel: u16, source: &mut Source<T>, closure: &mut dyn FnMut(&mut T))
+        -> io::Result<State>
+        where T: Serialize + Clone + Sync + Send + Default
+    {
+        let state = source.modify(closure);
+        self.record(channel, source.signal.heartbeat.generation(), source.current())?;
+        Ok(state)
+    }
+
+    /// Record the value of the sink, if it has changed since the last read. Publishes the sink
+    /// did not see are not recorded, so the sink needs to be tapped at least as often as the
+    /// source publishes to capture every value.
+    /// Returns true if a value has been recorded.
+    pub fn tap<T>(&mut self, channel: u16, sink: &Sink<T>) -> io::Result<bool>
+        where T: Serialize + Clone + Sync + Send + Default
+    {
+        if !sink.changed() {
+            return Ok(false)
+        }
+        let generation = sink.signal.heartbeat.generation();
+        let mut result = Ok(());
+        sink.process(&mut |value| result = self.record(channel, generation, value));
+        result.map(|_| true)
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// A recorded publish.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub channel: u16,
+    /// Time since the recorder was created.
+    pub time: Duration,
+    pub generation: u64,
+    payload: Vec<u8>,
+}
+
+impl Record {
+    /// Deserialize the recorded value.
+    pub fn value<T: DeserializeOwned>(&self) -> io::Result<T> {
+        bincode::deserialize(&self.payload).map_err(invalid_data)
+    }
+}
+
+/// Timing of a replay.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Timing {
+    /// Replay with the recorded timing.
+    Original,
+    /// Replay with the recorded timing scaled by the given speed, e.g. `2.0` replays twice as
+    /// fast. A speed which is not positive replays without waiting.
+    Scaled(f64),
+    /// Replay without waiting between the records.
+    Immediate,
+}
+
+/// Replays a log written by a [Recorder].
+pub struct Replayer<R: Read> {
+    reader: R,
+    time: u64, // nanoseconds of the last record
+}
+
+impl<R: Read> Replayer<R> {
+    /// Create a new replayer reading from the given reader, the header is checked immediately.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut header = [0; 8];
+        reader.read_exact(&mut header)?;
+        if header[..7] != MAGIC {
+            return Err(invalid_data("not an atomx record"))
+        }
+        if header[7] != VERSION {
+            return Err(invalid_data("unsupported record version"))
+        }
+        Ok(Replayer { reader, time: 0 })
+    }
+
+    /// Read the next record, returns `None` at the end of the log.
+    pub fn next_record(&mut self) -> io::Result<Option<Record>> {
+        let delta = match read_varint(&mut self.reader) {
+            Ok(delta) => delta,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let channel = u16::try_from(read_varint(&mut self.reader)?)
+            .map_err(|_| invalid_data("channel out of range"))?;
+        let generation = read_varint(&mut self.reader)?;
+        let len = read_varint(&mut self.reader)?;
+        // grows with the data actually read, a corrupt length does not allocate upfront
+        let mut payload = Vec::new();
+        (&mut self.reader).take(len).read_to_end(&mut payload)?;
+        if payload.len() as u64 != len {
+            return Err(invalid_data("truncated record"))
+        }
+        self.time += delta;
+        Ok(Some(Record { channel, time: Duration::from_nanos(self.time), generation, payload }))
+    }
+
+    /// Replay all remaining records with the given timing, by calling the closure for each.
+    /// Returns the number of replayed records.
+    pub fn replay(&mut self, timing: Timing, closure: &mut dyn FnMut(&Record) -> io::Result<()>)
+        -> io::Result<usize>
+    {
+        let start = Instant::now();
+        let mut count = 0;
+        while let Some(record) = self.next_record()? {
+            let due = match timing {
+                Timing::Original => Some(record.time),
+                // NaN is not positive either
+                Timing::Scaled(speed) if speed > 0.0 => Some(
+                    Duration::try_from_secs_f64(record.time.as_secs_f64() / speed).unwrap_or(Duration::MAX)
+                ),
+                Timing::Scaled(_) => None,
+                Timing::Immediate => None,
+            };
+            if let Some(wait) = due.and_then(|due| due.checked_sub(start.elapsed())) {
+                thread::sleep(wait);
+            }
+            closure(&record)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Replay all remaining records of the channel into the source.
+    /// Returns the number of replayed records.
+    pub fn replay_into<T>(&mut self, channel: u16, source: &mut Source<T>, timing: Timing)
+        -> io::Result<usize>
+        where T: DeserializeOwned + Clone + Sync + Send + Default
+    {
+        let mut count = 0;
+        self.replay(timing, &mut |record| {
+            if record.channel == channel {
+                source.send(&record.value()?);
+                count += 1;
+            }
+            Ok(())
+        })?;
+        Ok(count)
+    }
+}
+
+impl<R: Read> Iterator for Replayer<R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_record().transpose()
+    }
+}
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn write_varint(writer: &mut dyn Write, mut value: u64) -> io::Result<()> {
+    let mut buffer = [0; 10];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buffer[len] = byte;
+            len += 1;
+            break
+        }
+        buffer[len] = byte | 0x80;
+        len += 1;
+    }
+    writer.write_all(&buffer[..len])
+}
+
+fn read_varint(reader: &mut dyn Read) -> io::Result<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+        value |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value)
+        }
+    }
+    Err(invalid_data("variable length integer too long"))
+}
+
+
+#[test]
+fn varint_round_trip() {
+    for value in [0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+        let mut buffer = Vec::new();
+        write_varint(&mut buffer, value).expect("written");
+        assert_eq!(read_varint(&mut &buffer[..]).expect("read"), value);
+    }
+}
+
+#[test]
+fn replayer_rejects_other_data() {
+    assert!(Replayer::new(&b"NOTATMXR"[..]).is_err());
+}
+
+#[cfg(test)]
+fn corrupt_log(channel: u64, len: u64, payload: &[u8]) -> Vec<u8> {
+    let mut log = Recorder::new(Vec::new()).expect("recorder").into_inner();
+    for value in [0, channel, 0, len] {
+        write_varint(&mut log, value).expect("written");
+    }
+    log.extend_from_slice(payload);
+    log
+}
+
+#[test]
+fn replayer_rejects_corrupt_records() {
+    let truncated = corrupt_log(0, u64::MAX, &[1, 2, 3]);
+    let error = Replayer::new(&truncated[..]).expect("header").next_record().expect_err("truncated");
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    let channel = corrupt_log(u16::MAX as u64 + 1, 0, &[]);
+    let error = Replayer::new(&channel[..]).expect("header").next_record().expect_err("channel");
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn replay_with_invalid_speed_does_not_wait() {
+    let log = corrupt_log(0, 0, &[]);
+    for speed in [0.0, -1.0, f64::NAN] {
+        let replayed = Replayer::new(&log[..]).expect("header").replay(Timing::Scaled(speed), &mut |_| Ok(()));
+        assert_eq!(replayed.expect("replayed"), 1);
+    }
+}
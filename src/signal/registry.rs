@@ -0,0 +1,322 @@
+use std::{
+    any::type_name,
+    collections::BTreeSet,
+    fmt::{self, Write},
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use super::{Heartbeat, Source, sync, loom::Arc};
+
+/// A registry of named signals, to inspect a running system.
+/// Registering is opt-in, either in a scoped registry or in the [Registry::global] one.
+/// The registry only keeps the heartbeat of a signal (and the acknowledgements of a synced
+/// signal), so it neither keeps the signal alive nor counts as a sink.
+#[derive(Default)]
+pub struct Registry {
+    entries: Mutex<Vec<Entry>>,
+}
+
+struct Entry {
+    name: String,
+    type_name: &'static str,
+    heartbeat: Heartbeat,
+    handshake: Option<Arc<sync::Handshake>>,
+}
+
+/// Information about a registered signal at the time it was inspected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Info {
+    pub name: String,
+    pub type_name: &'static str,
+    pub sinks: u32,
+    pub generation: u64,
+    /// Time passed since the last publish.
+    pub since_publish: Duration,
+    /// False once the source has been dropped.
+    pub alive: bool,
+    /// Number of sinks a synced source is still waiting for, `None` if the signal is not synced.
+    pub pending: Option<u32>,
+    /// Owner label of the source.
+    pub producer: Option<String>,
+    /// Owner labels of the labeled sinks.
+    pub consumers: Vec<String>,
+}
+
+impl Info {
+    /// Check if a synced source waits for acknowledgements longer than the given time.
+    pub fn is_stalled(&self, after: Duration) -> bool {
+        self.pending.is_some_and(|pending| pending > 0) && self.since_publish > after
+    }
+}
+
+impl Registry {
+    /// Time after which a pending handshake is considered stalled by [Registry::to_dot].
+    pub const STALL: Duration = Duration::from_secs(1);
+
+    pub fn new() -> Self {
+        Registry { entries: Mutex::new(Vec::new()) }
+    }
+
+    /// Returns the registry shared by the whole process.
+    pub fn global() -> &'static Registry {
+        static GLOBAL: OnceLock<Registry> = OnceLock::new();
+        GLOBAL.get_or_init(Registry::new)
+    }
+
+    /// Register the signal of the source by name. A signal registered before under the same name
+    /// is replaced.
+    pub fn register<T>(&self, name: &str, source: &Source<T>) where T: Clone + Sync + Send + Default {
+        self.insert(Entry { name: name.to_owned(), type_name: type_name::<T>(), heartbeat: source.heartbeat(), handshake: None });
+    }
+
+    /// Register the signal of a synced source by name, including its acknowledgement state.
+    pub fn register_sync<T>(&self, name: &str, source: &sync::Source<T>) where T: Clone + Sync + Send + Default {
+        self.insert(Entry {
+            name: name.to_owned(),
+            type_name: type_name::<T>(),
+            heartbeat: source.heartbeat(),
+            handshake: Some(source.handshake.clone()),
+        });
+    }
+
+    /// Register any signal through its heartbeat, e.g. of a multi producer or history source.
+    pub fn register_heartbeat<T>(&self, name: &str, heartbeat: Heartbeat) {
+        self.insert(Entry { name: name.to_owned(), type_name: type_name::<T>(), heartbeat, handshake: None });
+    }
+
+    /// Remove the signal with the given name, returns false if it was not registered.
+    pub fn unregister(&self, name: &str) -> bool {
+        let mut entries = self.entries();
+        let len = entries.len();
+        entries.retain(|entry| entry.name != name);
+        entries.len() != len
+    }
+
+    /// Returns the number of registered signals.
+    pub fn len(&self) -> usize {
+        self.entries().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns information about the signal with the given name.
+    pub fn info(&self, name: &str) -> Option<Info> {
+        self.entries().iter().find(|entry| entry.name == name).map(Entry::info)
+    }
+
+    /// Returns information about all registered signals, in order of registration.
+    pub fn infos(&self) -> Vec<Info> {
+        self.entries().iter().map(Entry::info).collect()
+    }
+
+    /// Returns a human readable dump of all registered signals, one line per signal.
+    pub fn to_text(&self) -> String {
+        self.to_string()
+    }
+
+    /// Returns a JSON array of all registered signals.
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("[");
+        for (i, info) in self.infos().iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            let _ = write!(json,
+                "{{\"name\":{},\"type\":{},\"sinks\":{},\"generation\":{},\"since_publish_us\":{},\"alive\":{},\"pending\":{}}}",
+                quote(&info.name), quote(info.type_name), info.sinks, info.generation,
+                info.since_publish.as_micros(), info.alive,
+                info.pending.map_or_else(|| String::from("null"), |pending| pending.to_string()),
+            );
+        }
+        json.push(']');
+        json
+    }
+
+    /// Returns a Graphviz graph of producers, signals and consumers. Only labeled sources and
+    /// sinks are shown, see [Source::set_owner] and [super::Sink::set_owner]. Edges to the sinks
+    /// of synced signals are bold, signals with a handshake pending longer than
+    /// [Registry::STALL] are red.
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(Self::STALL)
+    }
+
+    /// Like [Registry::to_dot], with a custom time after which a handshake is stalled.
+    pub fn to_dot_with(&self, stall: Duration) -> String {
+        let infos = self.infos();
+        let owners: BTreeSet<&str> = infos.iter()
+            .flat_map(|info| info.producer.iter().chain(info.consumers.iter()))
+            .map(String::as_str)
+            .collect();
+        let mut dot = String::from("digraph signals {\n    rankdir=LR;\n");
+        for owner in owners {
+            let _ = writeln!(dot, "    {} [shape=ellipse];", quote(&format!("owner:{}", owner)));
+        }
+        for info in &infos {
+            let node = quote(&format!("signal:{}", info.name));
+            let color = match info.is_stalled(stall) {
+                true => ", color=red, fontcolor=red",
+                false => "",
+            };
+            let _ = writeln!(dot, "    {} [shape=box, label={}{}];",
+                node, quote(&format!("{} <{}>", info.name, info.type_name)), color);
+            if let Some(producer) = &info.producer {
+                let _ = writeln!(dot, "    {} -> {};", quote(&format!("owner:{}", producer)), node);
+            }
+            let consumers: BTreeSet<&String> = info.consumers.iter().collect();
+            for consumer in consumers {
+                let style = match (info.pending.is_some(), color.is_empty()) {
+                    (true, true) => " [style=bold, label=\"sync\"]",
+                    (true, false) => " [style=bold, label=\"sync\", color=red]",
+                    (false, _) => "",
+                };
+                let _ = writeln!(dot, "    {} -> {}{};", node, quote(&format!("owner:{}", consumer)), style);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn insert(&self, entry: Entry) {
+        entry.heartbeat.set_name(&entry.name);
+        let mut entries = self.entries();
+        match entries.iter_mut().find(|registered| registered.name == entry.name) {
+            Some(registered) => *registered = entry,
+            None => entries.push(entry),
+        }
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, Vec<Entry>> {
+        // the lock is only held for single operations on the vector, which leave it valid if they
+        // panic, at worst without the change of the panicking call, so poisoning is ignored
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Entry {
+    fn info(&self) -> Info {
+        Info {
+            name: self.name.clone(),
+            type_name: self.type_name,
+            sinks: self.heartbeat.sink_count(),
+            generation: self.heartbeat.generation(),
+            since_publish: self.heartbeat.elapsed(),
+            alive: self.heartbeat.is_alive(),
+            pending: self.handshake.as_ref().map(|handshake| match handshake.pending() {
+                u32::MAX => 0, // never sent
+                acks => acks,
+            }),
+            producer: self.heartbeat.source_owner(),
+            consumers: self.heartbeat.sink_owners(),
+        }
+    }
+}
+
+impl fmt::Display for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for info in self.infos() {
+            write!(f, "{} <{}> generation: {}, sinks: {}, last publish: {:?} ago",
+                info.name, info.type_name, info.generation, info.sinks, info.since_publish)?;
+            if let Some(pending) = info.pending {
+                write!(f, ", pending acks: {}", pending)?;
+            }
+            if !info.alive {
+                write!(f, ", source gone")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if (c as u32) < 0x20 => { let _ = write!(quoted, "\\u{:04x}", c as u32); }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+
+#[test]
+fn registry_reports_signal_state() {
+    let registry = Registry::new();
+    let mut source = Source::from(0u32);
+    let sink = source.sink();
+    let _other = sink.clone();
+    registry.register("counter", &source);
+    source.send(&1);
+
+    let info = registry.info("counter").expect("registered");
+    assert_eq!(info.type_name, "u32");
+    assert_eq!(info.sinks, 2);
+    assert_eq!(info.generation, 1);
+    assert!(info.alive);
+    assert_eq!(info.pending, None);
+
+    drop(source);
+    assert!(!registry.info("counter").expect("registered").alive);
+    assert!(registry.unregister("counter"));
+    assert!(registry.is_empty());
+}
+
+#[test]
+fn registry_reports_pending_acknowledgements() {
+    let registry = Registry::new();
+    let mut source = sync::Source::from(0u32);
+    let sink = source.sink();
+    let _other = source.sink();
+    registry.register_sync("synced", &source);
+    assert_eq!(registry.info("synced").expect("registered").pending, Some(0));
+    source.send(&1);
+    assert_eq!(registry.info("synced").expect("registered").pending, Some(2));
+    sink.receive();
+    assert_eq!(registry.info("synced").expect("registered").pending, Some(1));
+}
+
+#[test]
+fn registry_dumps_text_and_json() {
+    let registry = Registry::new();
+    let source = Source::from(String::new());
+    registry.register("say \"hi\"", &source);
+    registry.register("say \"hi\"", &source);
+    assert_eq!(registry.len(), 1);
+    assert!(registry.to_text().starts_with("say \"hi\" <alloc::string::String> generation: 0, sinks: 0"));
+    let json: serde_json::Value = serde_json::from_str(&registry.to_json()).expect("valid json");
+    assert_eq!(json[0]["name"], "say \"hi\"");
+    assert_eq!(json[0]["pending"], serde_json::Value::Null);
+}
+
+#[test]
+fn registry_exports_dataflow_graph() {
+    let registry = Registry::new();
+    let mut source = sync::Source::from(0u32);
+    source.set_owner("sensor");
+    let mut sink = source.sink();
+    sink.set_owner("control");
+    let mut logger = sink.clone();
+    logger.set_owner("logger");
+    registry.register_sync("speed", &source);
+
+    let info = registry.info("speed").expect("registered");
+    assert_eq!(info.producer.as_deref(), Some("sensor"));
+    assert_eq!(info.consumers, vec!["control", "logger"]);
+    let dot = registry.to_dot();
+    assert!(dot.contains("\"owner:sensor\" -> \"signal:speed\";"));
+    assert!(dot.contains("\"signal:speed\" -> \"owner:logger\" [style=bold, label=\"sync\"];"));
+    assert!(!dot.contains("red"));
+
+    source.send(&1);
+    assert!(registry.to_dot_with(Duration::ZERO).contains("color=red"));
+    drop(logger);
+    assert_eq!(registry.info("speed").expect("registered").consumers, vec!["control"]);
+}
@@ -0,0 +1,252 @@
+use std::{thread, time::{Duration, Instant}};
+
+/// A sink which can wait in a [Selector].
+pub trait Selectable {
+    /// Check if source has changed the signal, since last read.
+    fn changed(&self) -> bool;
+    /// Check if sink is connected.
+    fn is_connected(&self) -> bool;
+}
+
+macro_rules! selectable {
+    ($($sink:ty)*) => {$(
+        impl<T> Selectable for $sink where T: Clone + Sync + Send + Default {
+            fn changed(&self) -> bool {
+                <$sink>::changed(self)
+            }
+            fn is_connected(&self) -> bool {
+                <$sink>::is_connected(self)
+            }
+        }
+    )*}
+}
+selectable!(super::Sink<T> super::sync::Sink<T> super::history::Sink<T>);
+
+impl<T> Selectable for super::arc::Sink<T> where T: Send + Sync {
+    fn changed(&self) -> bool {
+        super::arc::Sink::changed(self)
+    }
+    fn is_connected(&self) -> bool {
+        super::arc::Sink::is_connected(self)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Error {
+    /// No sink has changed within the timeout.
+    Timeout,
+    /// No sink has changed and the sources of all sinks are gone.
+    AllGone,
+}
+
+/// Waits until any of a set of sinks has changed, the sinks may be of different types.
+/// Waiting polls the sinks with a backoff from spinning over yielding to sleeping, so a changed
+/// sink is noticed with at most a millisecond of delay. The sinks are checked round robin, to
+/// not starve a sink which changes less often than another.
+#[derive(Default)]
+pub struct Selector<'a> {
+    sinks: Vec<&'a dyn Selectable>,
+    next: usize,
+}
+
+const SPINS: u32 = 16;
+const YIELDS: u32 = 64;
+const MAX_SLEEP: Duration = Duration::from_millis(1);
+
+impl<'a> Selector<'a> {
+    pub fn new() -> Self {
+        Selector { sinks: Vec::new(), next: 0 }
+    }
+
+    /// Add a sink, returns its index.
+    pub fn add(&mut self, sink: &'a dyn Selectable) -> usize {
+        self.sinks.push(sink);
+        self.sinks.len() - 1
+    }
+
+    /// Returns the number of sinks.
+    pub fn len(&self) -> usize {
+        self.sinks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    /// Returns the index of a changed sink, without waiting.
+    /// The sink is not marked as read, that happens when the value is received from it.
+    pub fn try_select(&mut self) -> Result<usize, Error> {
+        let len = self.sinks.len();
+        let mut connected = false;
+        for offset in 0..len {
+            let index = (self.next + offset) % len;
+            let sink = self.sinks[index];
+            if sink.changed() {
+                self.next = (index + 1) % len;
+                return Ok(index)
+            }
+            connected |= sink.is_connected();
+        }
+        match connected {
+            true => Err(Error::Timeout),
+            false => Err(Error::AllGone),
+        }
+    }
+
+    /// Wait until a sink has changed and return its index, or until the timeout passed.
+    /// Without a timeout, waits until a sink has changed or all sources are gone.
+    pub fn select(&mut self, timeout: Option<Duration>) -> Result<usize, Error> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut sleep = Duration::from_micros(10);
+        let mut round: u32 = 0;
+        loop {
+            match self.try_select() {
+                Err(Error::Timeout) => {}
+                result => return result,
+            }
+            let remaining = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            if remaining == Some(Duration::ZERO) {
+                return Err(Error::Timeout)
+            }
+            match round {
+                round if round < SPINS => std::hint::spin_loop(),
+                round if round < YIELDS => thread::yield_now(),
+                _ => {
+                    thread::sleep(remaining.map_or(sleep, |remaining| remaining.min(sleep)));
+                    sleep = (sleep * 2).min(MAX_SLEEP);
+                }
+            }
+            round = round.saturating_add(1);
+        }
+    }
+}
+
+/// Wait until any of the given sinks has changed, receive its value and evaluate the matching
+/// arm. Evaluates to `Ok` with the value of the arm, or to a [Error] if no sink has changed within
+/// the optional timeout or all sources are gone. The sinks need to have a `receive` method, which
+/// is the case for plain, sync and history sinks. A sink expression is evaluated twice, so it
+/// should be a place like a variable or a field. The arms are evaluated in the surrounding code,
+/// so `break`, `continue`, `return` and `?` apply to it.
+///
+/// ```
+/// use std::time::Duration;
+/// use atomx::signal;
+///
+/// let (commands, command_sink) = signal::create::<u32>();
+/// command_sink.receive(); // nothing new from the commands
+/// let mut config = signal::sync::Source::from(String::new());
+/// let config_sink = config.sink();
+/// config.send(&String::from("fast"));
+///
+/// let selected = signal::select! {
+///     timeout = Duration::from_millis(10);
+///     command = command_sink => format!("command {}", command),
+///     config = config_sink => format!("config {}", config),
+/// };
+/// assert_eq!(selected, Ok(String::from("config fast")));
+/// # drop(commands);
+/// ```
+#[macro_export]
+macro_rules! select {
+    (timeout = $timeout:expr; $($name:pat = $sink:expr => $body:expr),+ $(,)?) => {
+        $crate::select!(@select Some($timeout); $($name = $sink => $body),+)
+    };
+    ($($name:pat = $sink:expr => $body:expr),+ $(,)?) => {
+        $crate::select!(@select None; $($name = $sink => $body),+)
+    };
+    (@select $timeout:expr; $($name:pat = $sink:expr => $body:expr),+) => {{
+        let mut selector = $crate::signal::Selector::new();
+        $( selector.add(&$sink); )+
+        match selector.select($timeout) {
+            Ok(selected) => {
+                let mut arm = 0;
+                $(
+                    if { arm += 1; arm - 1 == selected } {
+                        let $name = $sink.receive();
+                        Ok($body)
+                    } else
+                )+ {
+                    unreachable!()
+                }
+            }
+            Err(error) => Err(error),
+        }
+    }};
+}
+
+
+#[test]
+fn selector_returns_changed_sink() {
+    let (mut source1, sink1) = super::create::<u32>();
+    let source2 = super::sync::Source::from(0u32);
+    let sink2 = source2.sink();
+    let mut selector = Selector::new();
+    assert_eq!(selector.add(&sink1), 0);
+    assert_eq!(selector.add(&sink2), 1);
+    sink1.receive();
+    sink2.receive();
+    assert_eq!(selector.select(Some(Duration::from_millis(1))), Err(Error::Timeout));
+    source1.send(&1);
+    assert_eq!(selector.select(None), Ok(0));
+    drop(source1);
+    drop(source2);
+    assert_eq!(selector.select(Some(Duration::from_millis(1))), Ok(0));
+    sink1.receive();
+    assert_eq!(selector.select(None), Err(Error::AllGone));
+}
+
+#[test]
+fn selector_is_fair() {
+    let (mut source1, sink1) = super::create::<u32>();
+    let (mut source2, sink2) = super::create::<u32>();
+    let mut selector = Selector::new();
+    selector.add(&sink1);
+    selector.add(&sink2);
+    source1.send(&1);
+    source2.send(&2);
+    assert_eq!(selector.try_select(), Ok(0));
+    assert_eq!(selector.try_select(), Ok(1));
+}
+
+#[test]
+fn select_wakes_up_on_send_from_other_thread() {
+    let (mut source, sink) = super::create::<u32>();
+    let (_config, config_sink) = super::create::<String>();
+    sink.receive();
+    config_sink.receive();
+    let sender = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(5));
+        source.send(&7);
+        source
+    });
+    let selected = crate::select! {
+        config = config_sink => config.len() as u32,
+        value = sink => value,
+    };
+    assert_eq!(selected, Ok(7));
+    drop(sender.join());
+}
+
+#[test]
+fn select_arms_control_the_surrounding_loop() {
+    let (mut source, sink) = super::create::<u32>();
+    let mut received = Vec::new();
+    loop {
+        let selected = crate::select! {
+            timeout = Duration::from_millis(10);
+            value = sink => {
+                if value == 3 {
+                    break
+                }
+                received.push(value);
+                source.send(&(value + 1));
+                if value == 1 {
+                    continue
+                }
+                value
+            },
+        };
+        assert!(selected.is_ok());
+    }
+    assert_eq!(received, [0, 1, 2]);
+}
@@ -0,0 +1,313 @@
+use std::{
+    ffi::CString, io, mem::size_of, ptr::{self, NonNull},
+    sync::atomic::{fence, AtomicU32, AtomicU64, Ordering},
+};
+
+use crate::signal::sync::State;
+
+const MAGIC: u64 = u64::from_le_bytes(*b"ATOMXSHM");
+const VERSION: u32 = 1;
+
+/// Marker for types which can be shared between processes.
+///
+/// # Safety
+/// The type needs to be `#[repr(C)]` (or a primitive), must not contain pointers or references
+/// and every bit pattern must be a valid value, e.g. all zero.
+pub unsafe trait Plain: Copy + Send + Sync + 'static {}
+
+macro_rules! plain {
+    ($($t:ty)*) => { $(unsafe impl Plain for $t {})* }
+}
+plain!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize f32 f64);
+unsafe impl<T: Plain, const N: usize> Plain for [T; N] {}
+
+#[repr(C)]
+struct Header {
+    magic: u64,
+    version: u32,
+    size: u32,
+    align: u32,
+    pid: u32,   // process id of the source
+    alive: AtomicU32,
+    sinks: AtomicU32,
+    sequence: AtomicU64, // odd while the source writes
+}
+
+#[repr(C)]
+struct Shared<T> {
+    header: Header,
+    value: T,
+}
+
+/// A shared memory object mapped into this process.
+struct Mapping<T> {
+    ptr: NonNull<Shared<T>>,
+}
+
+// Safety: the mapping is only accessed through atomics and the sequence lock
+unsafe impl<T: Plain> Send for Mapping<T> {}
+unsafe impl<T: Plain> Sync for Mapping<T> {}
+
+impl<T: Plain> Mapping<T> {
+    fn open(name: &str, flags: libc::c_int) -> io::Result<Self> {
+        let name = shm_name(name)?;
+        let len = size_of::<Shared<T>>();
+        unsafe {
+            // Safety: name is a valid nul terminated string
+            let fd = libc::shm_open(name.as_ptr(), flags, 0o600);
+            if fd < 0 {
+                return Err(io::Error::last_os_error())
+            }
+            if flags & libc::O_CREAT != 0 && libc::ftruncate(fd, len as libc::off_t) < 0 {
+                let error = io::Error::last_os_error();
+                libc::close(fd);
+                libc::shm_unlink(name.as_ptr());
+                return Err(error)
+            }
+            if flags & libc::O_CREAT == 0 && !has_len(fd, len) {
+                libc::close(fd);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "shared memory size mismatch"))
+            }
+            // Safety: fd is a valid descriptor of an object of at least len bytes
+            let ptr = libc::mmap(
+                ptr::null_mut(), len, libc::PROT_READ | libc::PROT_WRITE, libc::MAP_SHARED, fd, 0
+            );
+            libc::close(fd);
+            if ptr == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error())
+            }
+            Ok(Mapping { ptr: NonNull::new_unchecked(ptr as *mut Shared<T>) })
+        }
+    }
+
+    fn header(&self) -> &Header {
+        // Safety: the mapping is valid while self exists and the header is initialized
+        unsafe { &self.ptr.as_ref().header }
+    }
+
+    fn value_ptr(&self) -> *mut T {
+        // Safety: the mapping is valid while self exists
+        unsafe { ptr::addr_of_mut!((*self.ptr.as_ptr()).value) }
+    }
+}
+
+impl<T> Drop for Mapping<T> {
+    fn drop(&mut self) {
+        // Safety: the mapping was created by mmap with this length
+        unsafe { libc::munmap(self.ptr.as_ptr() as *mut libc::c_void, size_of::<Shared<T>>()) };
+    }
+}
+
+fn shm_name(name: &str) -> io::Result<CString> {
+    let name = match name.starts_with('/') {
+        true => name.to_owned(),
+        false => format!("/{}", name),
+    };
+    CString::new(name).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+fn has_len(fd: libc::c_int, len: usize) -> bool {
+    // Safety: stat is plain old data and fully written by fstat on success
+    let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+    unsafe { libc::fstat(fd, &mut stat) == 0 && stat.st_size as usize >= len }
+}
+
+/// The writing end of a signal between processes on the same machine, over POSIX shared memory.
+/// The shared memory holds a versioned header and a single value, protected by a sequence lock.
+/// The source is never blocked by sinks, a sink reading while the source writes retries until it
+/// has read a consistent value. There must be only one source per name.
+pub struct Source<T: Plain> {
+    mapping: Mapping<T>,
+    name: CString,
+}
+
+impl<T: Plain> Source<T> {
+    /// Create the shared memory object with the given name, initialized to all zero.
+    /// Fails if an object with this name already exists.
+    pub fn create(name: &str) -> io::Result<Self> {
+        let mapping = Mapping::<T>::open(name, libc::O_CREAT | libc::O_EXCL | libc::O_RDWR)?;
+        unsafe {
+            // Safety: - the object was just created, no other process has initialized it yet
+            //         - sinks check the magic last, so they never see a partial header
+            let shared = mapping.ptr.as_ptr();
+            ptr::write_bytes(mapping.value_ptr(), 0, 1);
+            ptr::write(ptr::addr_of_mut!((*shared).header), Header {
+                magic: 0,
+                version: VERSION,
+                size: size_of::<T>() as u32,
+                align: std::mem::align_of::<T>() as u32,
+                pid: std::process::id(),
+                alive: AtomicU32::new(1),
+                sinks: AtomicU32::new(0),
+                sequence: AtomicU64::new(0),
+            });
+            fence(Ordering::Release);
+            ptr::write_volatile(ptr::addr_of_mut!((*shared).header.magic), MAGIC);
+        }
+        Ok(Source { mapping, name: shm_name(name)? })
+    }
+
+    /// Publish the data to the sinks.
+    pub fn send(&mut self, data: &T) -> State {
+        let header = self.mapping.header();
+        let sequence = header.sequence.load(Ordering::Relaxed);
+        header.sequence.store(sequence + 1, Ordering::Relaxed);
+        fence(Ordering::Release);
+        // Safety: - only this source writes the value
+        //         - sinks detect a concurrent write by the sequence and discard what they read
+        unsafe { ptr::write_volatile(self.mapping.value_ptr(), *data) };
+        header.sequence.store(sequence + 2, Ordering::Release);
+        match self.sink_count() {
+            0 => State::AllGone,
+            _ => State::Ready
+        }
+    }
+
+    /// Modify a copy of the current data and publish it to the sinks.
+    pub fn modify(&mut self, closure: &mut dyn FnMut(&mut T)) -> State {
+        // Safety: only this source writes the value, so it can not change while reading it
+        let mut value = unsafe { ptr::read_volatile(self.mapping.value_ptr()) };
+        closure(&mut value);
+        self.send(&value)
+    }
+
+    /// Returns the generation of the signal, which is the number of publishes so far.
+    pub fn generation(&self) -> u64 {
+        self.mapping.header().sequence.load(Ordering::Acquire) / 2
+    }
+
+    /// Returns the number of sinks which have opened the signal, in any process.
+    pub fn sink_count(&self) -> u32 {
+        self.mapping.header().sinks.load(Ordering::Acquire)
+    }
+}
+
+impl<T: Plain> Drop for Source<T> {
+    fn drop(&mut self) {
+        self.mapping.header().alive.store(0, Ordering::Release);
+        // Safety: name is a valid nul terminated string. Open mappings of sinks stay valid.
+        unsafe { libc::shm_unlink(self.name.as_ptr()) };
+    }
+}
+
+/// The reading end of a shared memory signal.
+pub struct Sink<T: Plain> {
+    mapping: Mapping<T>,
+    last_generation: AtomicU64,
+}
+
+impl<T: Plain> Sink<T> {
+    /// Open the shared memory object with the given name, created by a source.
+    /// Fails if the object does not exist or was created for a different type or version.
+    pub fn open(name: &str) -> io::Result<Self> {
+        let mapping = Mapping::<T>::open(name, libc::O_RDWR)?;
+        let header = mapping.header();
+        // Safety: the header is part of the mapping, the magic is written last by the source
+        let magic = unsafe { ptr::read_volatile(ptr::addr_of!(header.magic)) };
+        fence(Ordering::Acquire);
+        let invalid = |message| Err(io::Error::new(io::ErrorKind::InvalidData, message));
+        if magic != MAGIC {
+            return invalid("not an atomx signal")
+        }
+        if header.version != VERSION {
+            return invalid("unsupported version")
+        }
+        if header.size as usize != size_of::<T>() || header.align as usize != std::mem::align_of::<T>() {
+            return invalid("type layout mismatch")
+        }
+        header.sinks.fetch_add(1, Ordering::AcqRel);
+        Ok(Sink { mapping, last_generation: AtomicU64::new(u64::MAX) })
+    }
+
+    /// Returns a copy of the received signal value.
+    pub fn receive(&self) -> T {
+        let (value, generation) = self.read();
+        self.last_generation.store(generation, Ordering::Release);
+        value
+    }
+
+    /// Check if source has changed the signal, since last read.
+    pub fn changed(&self) -> bool {
+        self.generation() != self.last_generation.load(Ordering::Acquire)
+    }
+
+    /// Returns the generation of the signal, which is the number of publishes so far.
+    pub fn generation(&self) -> u64 {
+        self.mapping.header().sequence.load(Ordering::Acquire) / 2
+    }
+
+    /// Check if the source still exists.
+    pub fn is_connected(&self) -> bool {
+        self.mapping.header().alive.load(Ordering::Acquire) != 0
+    }
+
+    /// Returns the process id of the source.
+    pub fn source_pid(&self) -> u32 {
+        self.mapping.header().pid
+    }
+
+    fn read(&self) -> (T, u64) {
+        let header = self.mapping.header();
+        loop {
+            let before = header.sequence.load(Ordering::Acquire);
+            if before % 2 == 1 {
+                std::hint::spin_loop();
+                continue
+            }
+            // Safety: - every bit pattern is a valid T
+            //         - a value torn by a concurrent write is discarded below
+            let value = unsafe { ptr::read_volatile(self.mapping.value_ptr()) };
+            fence(Ordering::Acquire);
+            if header.sequence.load(Ordering::Relaxed) == before {
+                return (value, before / 2)
+            }
+        }
+    }
+}
+
+impl<T: Plain> Drop for Sink<T> {
+    fn drop(&mut self) {
+        self.mapping.header().sinks.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+
+#[cfg(test)]
+fn unique_name(test: &str) -> String {
+    format!("atomx-{}-{}", test, std::process::id())
+}
+
+#[test]
+fn sink_receives_sent_value() {
+    let name = unique_name("receive");
+    let mut source = Source::<[u32; 4]>::create(&name).expect("created");
+    let sink = Sink::<[u32; 4]>::open(&name).expect("opened");
+    assert_eq!(sink.receive(), [0; 4]);
+    assert!(!sink.changed());
+    assert_eq!(source.send(&[1, 2, 3, 4]), State::Ready);
+    assert!(sink.changed());
+    assert_eq!(sink.receive(), [1, 2, 3, 4]);
+    source.modify(&mut |value| value[0] = 5);
+    assert_eq!(sink.receive(), [5, 2, 3, 4]);
+    assert_eq!(sink.generation(), 2);
+    assert_eq!(sink.source_pid(), std::process::id());
+}
+
+#[test]
+fn sink_rejects_other_type() {
+    let name = unique_name("layout");
+    let _source = Source::<u32>::create(&name).expect("created");
+    assert!(Sink::<u64>::open(&name).is_err());
+}
+
+#[test]
+fn sink_is_not_connected_after_source_dropped() {
+    let name = unique_name("connected");
+    let source = Source::<u32>::create(&name).expect("created");
+    let sink = Sink::<u32>::open(&name).expect("opened");
+    assert!(sink.is_connected());
+    assert_eq!(source.sink_count(), 1);
+    drop(source);
+    assert!(!sink.is_connected());
+    assert!(Sink::<u32>::open(&name).is_err());
+}
@@ -0,0 +1,398 @@
+//! Deterministic simulation of signal based systems.
+//!
+//! Components which sample their sinks and publish to their sources at different rates are run
+//! on a single thread against a virtual clock, instead of real threads and real time. A seed
+//! decides the phase of each component and the order of components which are due at the same
+//! time, so a run is reproduced exactly from its seed.
+//! Components declare the signals they publish and sample, so the dataflow between them can be
+//! reported.
+//!
+//! ```
+//! use std::time::Duration;
+//! use atomx::signal::{self, sim::{Flow, Simulation}};
+//!
+//! let (mut speed, speed_sink) = signal::create::<u32>();
+//! let (mut alarm, alarm_sink) = signal::create::<bool>();
+//! let (speed_signal, alarm_signal) = (speed.heartbeat(), alarm.heartbeat());
+//!
+//! let mut sim = Simulation::new(42);
+//! let mut tick = 0;
+//! let sensor = sim.add("sensor", Duration::from_millis(10), move |_| {
+//!     tick += 1;
+//!     speed.send(&(tick * 10));
+//! });
+//! sim.declare(sensor, &[speed_signal.clone()], &[]);
+//! let monitor = sim.add("monitor", Duration::from_millis(25), move |_| {
+//!     alarm.send(&(speed_sink.receive() > 100));
+//! });
+//! sim.declare(monitor, &[alarm_signal], &[speed_signal]);
+//! assert_eq!(sim.dataflow(), vec![Flow { from: sensor, to: monitor, signal: None }]);
+//! sim.run_for(Duration::from_millis(200));
+//! assert!(alarm_sink.receive());
+//! ```
+
+use std::{
+    cmp::Reverse, collections::BinaryHeap, ops::Range, panic::{self, AssertUnwindSafe},
+    sync::{Arc, atomic::{AtomicU64, Ordering}}, time::Duration,
+};
+
+use super::Heartbeat;
+
+/// A part of a simulated system, which samples its sinks and publishes to its sources.
+/// The sources and sinks are owned by the component, e.g. moved into a closure.
+pub trait Component {
+    /// Run one step of the component at the given virtual time.
+    fn step(&mut self, now: Duration);
+
+    /// Returns the heartbeats of the signals the component publishes, see [Simulation::dataflow].
+    fn sources(&self) -> Vec<Heartbeat> {
+        Vec::new()
+    }
+
+    /// Returns the heartbeats of the signals the component samples, see [Simulation::dataflow].
+    fn sinks(&self) -> Vec<Heartbeat> {
+        Vec::new()
+    }
+}
+
+impl<F> Component for F where F: FnMut(Duration) {
+    fn step(&mut self, now: Duration) {
+        self(now)
+    }
+}
+
+/// The virtual time of a simulation, it only advances between the steps of components.
+#[derive(Debug, Clone, Default)]
+pub struct Clock(Arc<AtomicU64>);
+
+impl Clock {
+    /// Returns the virtual time since the start of the simulation.
+    pub fn now(&self) -> Duration {
+        Duration::from_nanos(self.0.load(Ordering::Acquire))
+    }
+
+    fn set(&self, now: Duration) {
+        self.0.store(now.as_nanos() as u64, Ordering::Release)
+    }
+}
+
+/// A step of a component at a virtual time, the trace of a simulation is the list of its steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub time: Duration,
+    pub component: usize,
+}
+
+/// A signal published by one component and sampled by another, see [Simulation::dataflow].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flow {
+    pub from: usize,
+    pub to: usize,
+    /// The name of the signal, if it is registered in a [super::Registry].
+    pub signal: Option<String>,
+}
+
+struct Entry {
+    name: String,
+    period: Duration,
+    jitter: Duration,
+    nominal: Duration, // the next due time without jitter
+    sources: Vec<Heartbeat>,
+    sinks: Vec<Heartbeat>,
+    component: Box<dyn Component>,
+}
+
+/// Runs components on a single thread, each once per period of virtual time.
+/// The first step of a component is at a random offset within its period, and each step may be
+/// delayed by a random jitter, which does not accumulate. Components due at the same time run in
+/// a random order.
+pub struct Simulation {
+    seed: u64,
+    rng: Rng,
+    clock: Clock,
+    entries: Vec<Entry>,
+    queue: BinaryHeap<Reverse<(Duration, u64, usize)>>, // due time, random order, component
+    trace: Vec<Step>,
+}
+
+impl Simulation {
+    pub fn new(seed: u64) -> Self {
+        Simulation {
+            seed,
+            rng: Rng(seed),
+            clock: Clock::default(),
+            entries: Vec::new(),
+            queue: BinaryHeap::new(),
+            trace: Vec::new(),
+        }
+    }
+
+    /// Returns the seed of the simulation, which reproduces it.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the virtual clock, for components which need the time outside of a step.
+    pub fn clock(&self) -> Clock {
+        self.clock.clone()
+    }
+
+    /// Returns the current virtual time.
+    pub fn now(&self) -> Duration {
+        self.clock.now()
+    }
+
+    /// Add a component which runs once per period, returns its index.
+    pub fn add(&mut self, name: &str, period: Duration, component: impl Component + 'static) -> usize {
+        self.add_with_jitter(name, period, Duration::ZERO, component)
+    }
+
+    /// Add a component which runs once per period, each step delayed by up to the jitter.
+    /// Returns the index of the component.
+    pub fn add_with_jitter(&mut self, name: &str, period: Duration, jitter: Duration,
+        component: impl Component + 'static) -> usize
+    {
+        assert!(period > Duration::ZERO, "a component needs a period");
+        let index = self.entries.len();
+        let nominal = self.now() + self.rng.below(period);
+        let (sources, sinks) = (component.sources(), component.sinks());
+        self.entries.push(Entry {
+            name: name.to_owned(), period, jitter, nominal, sources, sinks, component: Box::new(component)
+        });
+        self.schedule(index);
+        index
+    }
+
+    /// Declare signals the component at the given index publishes and samples, in addition to
+    /// the ones of [Component::sources] and [Component::sinks], e.g. for a closure.
+    pub fn declare(&mut self, index: usize, sources: &[Heartbeat], sinks: &[Heartbeat]) {
+        let entry = &mut self.entries[index];
+        entry.sources.extend_from_slice(sources);
+        entry.sinks.extend_from_slice(sinks);
+    }
+
+    /// Returns the signals passed between the components, ordered by the publishing component.
+    /// Only signals declared by both components are found.
+    pub fn dataflow(&self) -> Vec<Flow> {
+        let mut flows = Vec::new();
+        for (from, publisher) in self.entries.iter().enumerate() {
+            for source in &publisher.sources {
+                for (to, sampler) in self.entries.iter().enumerate() {
+                    if sampler.sinks.iter().any(|sink| sink.is_same(source)) {
+                        flows.push(Flow { from, to, signal: source.name() });
+                    }
+                }
+            }
+        }
+        flows
+    }
+
+    /// Returns the name of the component at the given index.
+    pub fn name(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(|entry| entry.name.as_str())
+    }
+
+    /// Returns the steps taken so far.
+    pub fn trace(&self) -> &[Step] {
+        &self.trace
+    }
+
+    /// Run the next due component, advancing the clock to its due time.
+    /// A panic of the component is resumed with the seed, the component and the time prepended to
+    /// its message.
+    pub fn step(&mut self) -> Option<Step> {
+        let Reverse((time, _, index)) = self.queue.pop()?;
+        self.clock.set(time);
+        let entry = &mut self.entries[index];
+        if let Err(panic) = panic::catch_unwind(AssertUnwindSafe(|| entry.component.step(time))) {
+            let message = panic.downcast_ref::<&str>().copied()
+                .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("non-string panic payload");
+            panic::resume_unwind(Box::new(format!(
+                "simulation with seed {} failed in {} at {:?}: {}", self.seed, entry.name, time, message
+            )))
+        }
+        entry.nominal += entry.period;
+        self.schedule(index);
+        let step = Step { time, component: index };
+        self.trace.push(step);
+        Some(step)
+    }
+
+    /// Run all steps due within the given duration from now, returns the number of steps.
+    pub fn run_for(&mut self, duration: Duration) -> usize {
+        let end = self.now() + duration;
+        let mut steps = 0;
+        while self.next_due().is_some_and(|due| due <= end) {
+            self.step();
+            steps += 1;
+        }
+        self.clock.set(end);
+        steps
+    }
+
+    /// Run steps until the condition holds after a step, or the given duration from now passed.
+    /// Returns if the condition holds.
+    pub fn run_until(&mut self, duration: Duration, condition: &mut dyn FnMut() -> bool) -> bool {
+        let end = self.now() + duration;
+        while self.next_due().is_some_and(|due| due <= end) {
+            self.step();
+            if condition() {
+                return true
+            }
+        }
+        self.clock.set(end);
+        false
+    }
+
+    fn next_due(&self) -> Option<Duration> {
+        self.queue.peek().map(|Reverse((due, _, _))| *due)
+    }
+
+    fn schedule(&mut self, index: usize) {
+        let entry = &self.entries[index];
+        let (nominal, jitter) = (entry.nominal, entry.jitter);
+        let delay = match jitter.is_zero() {
+            true => Duration::ZERO,
+            false => self.rng.below(jitter),
+        };
+        let order = self.rng.next();
+        self.queue.push(Reverse((nominal + delay, order, index)))
+    }
+}
+
+/// Run a simulation for each of the seeds over the given duration. The setup adds the components
+/// to a new simulation, a failing seed is reported in the panic of [Simulation::step].
+pub fn check(seeds: Range<u64>, duration: Duration, setup: &mut dyn FnMut(&mut Simulation)) {
+    for seed in seeds {
+        let mut simulation = Simulation::new(seed);
+        setup(&mut simulation);
+        simulation.run_for(duration);
+    }
+}
+
+/// A small pseudo random generator (splitmix64), which is stable across platforms and versions.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a random duration below the given one, in whole nanoseconds.
+    fn below(&mut self, limit: Duration) -> Duration {
+        Duration::from_nanos(self.next() % limit.as_nanos() as u64)
+    }
+}
+
+
+#[cfg(test)]
+fn producer_and_sampler(simulation: &mut Simulation) -> super::Sink<Vec<u32>> {
+    let (mut source, sink) = super::create::<u32>();
+    let (mut samples, samples_sink) = super::create::<Vec<u32>>();
+    let mut value = 0;
+    simulation.add_with_jitter("producer", Duration::from_millis(10), Duration::from_millis(3), move |_| {
+        value += 1;
+        source.send(&value);
+    });
+    let mut sampled = Vec::new();
+    simulation.add("sampler", Duration::from_millis(10), move |_| {
+        sampled.push(sink.receive());
+        samples.send(&sampled);
+    });
+    samples_sink
+}
+
+#[test]
+fn same_seed_reproduces_the_run() {
+    let run = |seed| {
+        let mut simulation = Simulation::new(seed);
+        let samples = producer_and_sampler(&mut simulation);
+        simulation.run_for(Duration::from_secs(1));
+        (simulation.trace().to_vec(), samples.receive())
+    };
+    assert_eq!(run(3), run(3));
+    assert_ne!(run(3).0, run(4).0);
+}
+
+#[test]
+fn components_run_at_their_rates() {
+    let mut simulation = Simulation::new(1);
+    let fast = simulation.add("fast", Duration::from_millis(10), |_| {});
+    let slow = simulation.add("slow", Duration::from_millis(25), |_| {});
+    assert_eq!(simulation.name(slow), Some("slow"));
+    simulation.run_for(Duration::from_secs(1));
+    let count = |index| simulation.trace().iter().filter(|step| step.component == index).count();
+    assert_eq!(count(fast), 100);
+    assert_eq!(count(slow), 40);
+    assert_eq!(simulation.now(), Duration::from_secs(1));
+    assert!(simulation.trace().windows(2).all(|steps| steps[0].time <= steps[1].time));
+}
+
+#[test]
+fn run_until_stops_at_condition() {
+    let mut simulation = Simulation::new(9);
+    let samples = producer_and_sampler(&mut simulation);
+    let clock = simulation.clock();
+    assert!(simulation.run_until(Duration::from_secs(1), &mut || samples.receive().len() == 5));
+    assert!(clock.now() < Duration::from_millis(60));
+    assert!(!simulation.run_until(Duration::from_millis(1), &mut || false));
+}
+
+#[test]
+#[should_panic(expected = "sampled twice")]
+fn check_resumes_the_panic_of_a_component() {
+    check(0..100, Duration::from_millis(100), &mut |simulation| {
+        let samples = producer_and_sampler(simulation);
+        simulation.add("checker", Duration::from_millis(10), move |_| {
+            let samples = samples.receive();
+            assert!(samples.windows(2).all(|pair| pair[0] != pair[1]), "sampled twice");
+        });
+    });
+}
+
+#[test]
+fn panic_names_seed_and_component() {
+    let panic = panic::catch_unwind(|| check(5..6, Duration::from_millis(100), &mut |simulation| {
+        simulation.add("failing", Duration::from_millis(10), |_| panic!("broken"));
+    })).expect_err("component panics");
+    let message = panic.downcast_ref::<String>().expect("formatted message");
+    assert!(message.starts_with("simulation with seed 5 failed in failing at "), "{}", message);
+    assert!(message.ends_with(": broken"), "{}", message);
+}
+
+#[test]
+fn dataflow_connects_declared_signals() {
+    struct Relay(super::Sink<u32>, super::Source<u32>);
+    impl Component for Relay {
+        fn step(&mut self, _: Duration) {
+            let value = self.0.receive();
+            self.1.send(&value);
+        }
+        fn sources(&self) -> Vec<Heartbeat> {
+            vec![self.1.heartbeat()]
+        }
+        fn sinks(&self) -> Vec<Heartbeat> {
+            vec![self.0.heartbeat()]
+        }
+    }
+    let registry = super::Registry::new();
+    let (input, input_sink) = super::create::<u32>();
+    let (output, output_sink) = super::create::<u32>();
+    registry.register("output", &output);
+
+    let mut simulation = Simulation::new(0);
+    let producer = simulation.add("producer", Duration::from_millis(10), |_| {});
+    simulation.declare(producer, &[input.heartbeat()], &[]);
+    let relay = simulation.add("relay", Duration::from_millis(10), Relay(input_sink, output));
+    let consumer = simulation.add("consumer", Duration::from_millis(10), |_| {});
+    simulation.declare(consumer, &[], &[output_sink.heartbeat()]);
+    assert_eq!(simulation.dataflow(), vec![
+        Flow { from: producer, to: relay, signal: None },
+        Flow { from: relay, to: consumer, signal: Some("output".to_owned()) },
+    ]);
+}
@@ -1,11 +1,17 @@
-use super::{*, loom::{Arc, atomic::AtomicU64}};
-use std::sync::atomic::Ordering;
+use super::{*, loom::{Arc, AtomicGeneration, Generation}};
+#[cfg(feature = "std")]
+use alloc::string::String;
+use core::sync::atomic::Ordering;
 
 // Sink
 #[derive(Default)]
 pub struct Sink<T> where T: Clone + Sync + Send + Default {
-    signal: Arc<Signal<T>>,
-    last_id: AtomicU64
+    pub(super) signal: Arc<Signal<T>>,
+    last_generation: AtomicGeneration,
+    #[cfg(feature = "std")]
+    owner: Option<String>,
+    #[cfg(feature = "metrics")]
+    counters: stats::SinkCounters,
 }
 
 
@@ -16,14 +22,30 @@ impl<T> Sink<T> where T: Clone + Sync + Send + Default {
     /// This is the only way to create a Sink. That's necessary to guaranty that both share the
     /// same sync property.
     pub fn from(source: &Source<T>) -> Self {
-        Sink { signal: source.signal(), last_id: AtomicU64::from(0) }
+        Sink::new(source.signal())
+    }
+
+    pub(super) fn new(signal: Arc<Signal<T>>) -> Self {
+        signal.heartbeat.connect();
+        // all data is new to a sink
+        Sink {
+            signal,
+            last_generation: AtomicGeneration::new(Generation::MAX),
+            #[cfg(feature = "std")]
+            owner: None,
+            #[cfg(feature = "metrics")]
+            counters: Default::default(),
+        }
     }
 
     /// Returns a copy of the received signal value.
     /// This is especially useful for small or primitive types. If the signal data is to expansive
     /// to copy have a look at [process].
     pub fn receive(&self) -> T {
-        self.last_id.store(self.signal.box_id(), Ordering::Release);
+        let generation = self.signal.heartbeat.raw_generation();
+        self.last_generation.store(generation, Ordering::Release);
+        #[cfg(feature = "metrics")]
+        self.counters.read(self.signal.heartbeat.counters(), generation, Default::default());
         self.signal.value().0
     }
 
@@ -32,8 +54,15 @@ impl<T> Sink<T> where T: Clone + Sync + Send + Default {
     /// creates back pressure onto the sender if processing takes to much time (even if not
     /// synced).
     pub fn process(&self, closure: &mut dyn FnMut(&T)) {
-        self.last_id.store(self.signal.box_id(), Ordering::Release);
+        let generation = self.signal.heartbeat.raw_generation();
+        self.last_generation.store(generation, Ordering::Release);
+        #[cfg(not(feature = "metrics"))]
         self.signal.process(closure);
+        #[cfg(feature = "metrics")]
+        {
+            let processed = stats::timed(|| { self.signal.process(closure); });
+            self.counters.read(self.signal.heartbeat.counters(), generation, processed);
+        }
     }
 
     /// Check if sink is connected.
@@ -42,16 +71,53 @@ impl<T> Sink<T> where T: Clone + Sync + Send + Default {
     }
 
     /// Check if source has changed the signal, since last read.
+    /// The generation is compared instead of the address of the value, because the address of a
+    /// boxed value may be reused after it was reclaimed.
     pub fn changed(&self) -> bool {
-       self.signal.box_id() != self.last_id.load(Ordering::Acquire)
+       self.signal.heartbeat.raw_generation() != self.last_generation.load(Ordering::Acquire)
+    }
+
+    /// Returns the heartbeat of the signal, to observe the liveness of the source.
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.signal.heartbeat.clone()
+    }
+
+    /// Label the sink with the component owning it, e.g. for [Registry::to_dot].
+    #[cfg(feature = "std")]
+    pub fn set_owner(&mut self, owner: &str) {
+        self.signal.heartbeat.relabel_sink(self.owner.as_deref(), Some(owner));
+        self.owner = Some(owner.to_owned());
     }
 
+    /// Returns the owner label of the sink, if it has been set.
+    #[cfg(feature = "std")]
+    pub fn owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+
+    /// Returns the statistics of this sink.
+    #[cfg(feature = "metrics")]
+    pub fn stats(&self) -> stats::Stats {
+        self.counters.stats(self.signal.heartbeat.counters())
+    }
 }
 
 impl<T> Clone for Sink<T> where T: Clone + Sync + Send + Default {
     fn clone(&self) -> Self {
-        Sink { signal: self.signal.clone(), last_id: AtomicU64::default()}
+        #[allow(unused_mut)] // only labeled with std
+        let mut sink = Sink::new(self.signal.clone());
+        #[cfg(feature = "std")]
+        if let Some(owner) = &self.owner {
+            sink.set_owner(owner);
+        }
+        sink
     }
 }
 
-
+impl<T> Drop for Sink<T> where T: Clone + Sync + Send + Default {
+    fn drop(&mut self) {
+        self.signal.heartbeat.disconnect();
+        #[cfg(feature = "std")]
+        self.signal.heartbeat.relabel_sink(self.owner.as_deref(), None)
+    }
+}
@@ -0,0 +1,78 @@
+use serde::{Serialize, Deserialize, Serializer, ser::SerializeStruct};
+
+use super::{Source, Sink};
+
+/// The value of a signal together with its generation, e.g. to persist the state of a source
+/// across restarts. A snapshot is taken by serializing a source, see [Source::restore].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot<T> {
+    pub generation: u64,
+    pub value: T,
+}
+
+impl<T> Source<T> where T: Clone + Sync + Send + Default {
+    /// Create a new source from a snapshot. The source continues counting the generation from
+    /// the snapshot.
+    pub fn restore(snapshot: Snapshot<T>) -> Self {
+        let source = Source::from(snapshot.value);
+        source.signal.heartbeat.restore(snapshot.generation);
+        source
+    }
+
+    /// Returns a snapshot of the last published value.
+    pub fn snapshot(&self) -> Snapshot<T> {
+        Snapshot {
+            generation: self.signal.heartbeat.generation(),
+            value: self.current().clone(),
+        }
+    }
+}
+
+/// A source is serialized as [Snapshot] of the last published value, without copying it.
+impl<T> Serialize for Source<T> where T: Serialize + Clone + Sync + Send + Default {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut snapshot = serializer.serialize_struct("Snapshot", 2)?;
+        snapshot.serialize_field("generation", &self.signal.heartbeat.generation())?;
+        snapshot.serialize_field("value", self.current())?;
+        snapshot.end()
+    }
+}
+
+/// A sink is serialized as the current value of the signal, without copying it.
+impl<T> Serialize for Sink<T> where T: Serialize + Clone + Sync + Send + Default {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut serializer = Some(serializer);
+        let mut result = None;
+        self.signal.process(&mut |value| {
+            result = serializer.take().map(|serializer| value.serialize(serializer))
+        });
+        result.expect("processed")
+    }
+}
+
+
+#[test]
+fn source_is_restored_from_snapshot() {
+    let (mut source, _sink) = super::create::<Vec<u32>>();
+    source.send(&vec![1, 2]);
+    source.send(&vec![3]);
+    let json = serde_json::to_string(&source).expect("serialized");
+    assert_eq!(json, r#"{"generation":2,"value":[3]}"#);
+
+    let snapshot: Snapshot<Vec<u32>> = serde_json::from_str(&json).expect("deserialized");
+    assert_eq!(snapshot, source.snapshot());
+    let mut restored = Source::restore(snapshot);
+    let sink = restored.sink();
+    assert!(sink.changed());
+    assert_eq!(sink.receive(), vec![3]);
+    restored.send(&vec![4]);
+    assert_eq!(restored.heartbeat().generation(), 3);
+}
+
+#[test]
+fn sink_is_serialized_as_value() {
+    let (mut source, sink) = super::create::<u32>();
+    source.send(&7);
+    assert_eq!(serde_json::to_string(&sink).expect("serialized"), "7");
+    assert!(sink.changed(), "serializing is not reading");
+}
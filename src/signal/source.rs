@@ -1,48 +1,180 @@
 use crate::signal::sync::State;
-use super::{*, loom::Arc, memory::Memory};
+use super::{*, loom::Arc};
 
-use std::pin::Pin;
+#[cfg(feature = "std")]
+use alloc::string::String;
+
+/// Synchronizes the write slot with the last published value, before [Source::modify] runs, see
+/// [Source::sync_forward]. Unlike a clone, it can copy only what differs.
+pub trait SyncFrom {
+    fn sync_from(&mut self, latest: &Self);
+}
+
+/// A value which is updated by patches, see [Source::apply]. Applying a patch should cost in
+/// the size of the change, not in the size of the value.
+pub trait Patchable {
+    type Patch;
+    fn apply(&mut self, patch: &Self::Patch);
+}
+
+type Patch<T> = Box<dyn Fn(&mut T) + Send + Sync>;
 
 // Source
 pub struct Source<T:Send + Default> {
     pub(super) signal: Arc<Signal<T>>,
-    pub(super) memory: Pin<Box<Memory<T>>>
+    sync_from: Option<fn(&mut T, &T)>, // synchronizes the write slot before modify, if set
+    stale: bool, // the write slot is behind the last published value
+    touched: bool, // the write slot has been accessed since the last publish
+    pending: Option<Patch<T>>, // the patch of the last publish, not yet applied to the write slot
 }
 
 impl<T:Send> Source<T> where T: Clone + Sync + Default {
     /// Create a new source from a given value.
     pub fn from(value: T) -> Self {
-        let mut memory: Pin<Box<Memory<T>>> =  Memory::new(value);
-        let pointer = memory.new_read_ptr();
         Source {
-            signal: Arc::new(Signal::new(pointer)),
-            memory
+            signal: Arc::new(Signal::new(value)),
+            sync_from: None,
+            stale: true,
+            touched: false,
+            pending: None,
         }
     }
 
     /// Modify the current data and publish the changes to the sinks. The data will be cloned once.
     pub fn send(&mut self, data: &T) -> State {
-        self.memory.write(data);
-        self.signal.swap(&mut self.memory);
-        match self.sink_count() {
-            0 => State::AllGone,
-            _ => State::Ready
+        #[cfg(feature = "tracing")]
+        let _span = trace::send(&self.signal.heartbeat).entered();
+        // Safety: the source is the single writer and &mut self prevents a reference of current
+        unsafe {
+            self.signal.memory().write(data);
+            self.publish();
         }
+        self.state()
     }
 
     /// Modify the current data with zero copy and publish the changes to the sinks.
+    /// The data is the one published before the last one, unless the source forwards the last
+    /// published data, see [Source::copy_forward], or the last publish was a [Source::apply],
+    /// whose patch brings the data up to date without a clone.
     pub fn modify(&mut self, closure: &mut dyn FnMut(&mut T)) -> State {
-        self.memory.write_in_place(closure);
-        self.signal.swap(&mut self.memory);
-        match self.sink_count() {
+        #[cfg(feature = "tracing")]
+        let _span = trace::modify(&self.signal.heartbeat).entered();
+        // Safety: the source is the single writer and &mut self prevents a reference of current
+        unsafe {
+            self.forward(self.sync_from);
+            self.signal.memory().write_in_place(closure);
+            self.publish();
+        }
+        self.state()
+    }
+
+    /// Modify the current data with zero copy and publish the changes to the sinks, only if the
+    /// closure succeeds. On an error nothing is published and the data is restored to the last
+    /// published data, so the next modification starts from there.
+    pub fn try_modify<E>(&mut self, closure: &mut dyn FnMut(&mut T) -> Result<(), E>) -> Result<State, E> {
+        #[cfg(feature = "tracing")]
+        let _span = trace::modify(&self.signal.heartbeat).entered();
+        // Safety: the source is the single writer and &mut self prevents a reference of current
+        unsafe {
+            self.forward(self.sync_from);
+            if let Err(error) = self.signal.memory().try_write_in_place(closure) {
+                self.stale = false;
+                self.touched = false;
+                return Err(error)
+            }
+            self.publish();
+        }
+        Ok(self.state())
+    }
+
+    /// Apply the patch to the current data and publish it to the sinks. The patch is kept to be
+    /// applied to the other slot before it is written next, so neither slot is cloned, except
+    /// once to start from the last published data.
+    pub fn apply(&mut self, patch: T::Patch) -> State where T: Patchable, T::Patch: Send + Sync + 'static {
+        #[cfg(feature = "tracing")]
+        let _span = trace::modify(&self.signal.heartbeat).entered();
+        let touched = self.touched;
+        // Safety: the source is the single writer and &mut self prevents a reference of current
+        unsafe {
+            // a patch always goes onto the last published data
+            self.forward(Some(self.sync_from.unwrap_or(<T as Clone>::clone_from)));
+            self.signal.memory().write_in_place(&mut |slot| slot.apply(&patch));
+            self.publish();
+        }
+        // the other slot only misses the patch, unless this one has been accessed
+        if !touched {
+            self.pending = Some(Box::new(move |slot: &mut T| slot.apply(&patch)));
+        }
+        self.state()
+    }
+
+    fn state(&self) -> State {
+        let state = match self.sink_count() {
             0 => State::AllGone,
             _ => State::Ready
-        }
+        };
+        #[cfg(feature = "tracing")]
+        trace::published(&self.signal.heartbeat, &state);
+        state
     }
 
     /// Access the current data without publishing the change to the sinks.
     pub fn access(&mut self, closure: &mut dyn FnMut(&mut T)) {
-        self.memory.write_in_place(closure)
+        // Safety: the source is the single writer and &mut self prevents a reference of current
+        unsafe {
+            self.forward(self.sync_from);
+            self.signal.memory().write_in_place(closure)
+        }
+        self.touched = true
+    }
+
+    /// Let [Source::modify] start from the last published data, by cloning it into the write
+    /// slot first. Otherwise modify starts from the data published before the last one, as the
+    /// signal alternates between two slots.
+    pub fn copy_forward(&mut self) {
+        self.sync_from = Some(<T as Clone>::clone_from)
+    }
+
+    /// Like [Source::copy_forward], but the write slot is synchronized by [SyncFrom].
+    pub fn sync_forward(&mut self) where T: SyncFrom {
+        self.sync_from = Some(T::sync_from)
+    }
+
+    /// Bring the write slot up to date with the last published data, by the pending patch or
+    /// else by the given synchronization. Without either, the slot stays behind.
+    /// # Safety
+    /// The same as for [Memory::write].
+    unsafe fn forward(&mut self, sync_from: Option<fn(&mut T, &T)>) {
+        if !self.stale {
+            return
+        }
+        let memory = self.signal.memory();
+        if let Some(patch) = self.pending.take() {
+            memory.write_in_place(&mut |slot| patch(slot));
+        } else if let Some(sync_from) = sync_from {
+            let latest = memory.current();
+            memory.write_in_place(&mut |slot| sync_from(slot, latest));
+        } else {
+            return
+        }
+        self.stale = false
+    }
+
+    /// Publish the write slot, the new write slot is behind by one publish.
+    /// # Safety
+    /// The same as for [Memory::write].
+    unsafe fn publish(&mut self) {
+        self.signal.swap();
+        self.stale = true;
+        self.touched = false;
+        self.pending = None
+    }
+
+    /// Returns the last published data.
+    #[cfg(feature = "std")]
+    pub(super) fn current(&self) -> &T {
+        // Safety: the source is the single writer and does not write while &self is borrowed
+        unsafe { self.signal.memory().current() }
     }
 
     pub(super) fn signal(&self) -> Arc<Signal<T>> {
@@ -62,7 +194,37 @@ impl<T:Send> Source<T> where T: Clone + Sync + Default {
 
     /// Check if the given data equals the last published data.
     pub fn equals_last(&self, data: &T) -> bool where T: PartialEq {
-        self.memory.equals_current(data)
+        // Safety: the source is the single writer and does not write while &self is borrowed
+        unsafe { self.signal.memory().equals_current(data) }
+    }
+
+    /// Returns the heartbeat of the signal, which tracks the publishes of this source.
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.signal.heartbeat.clone()
+    }
+
+    /// Label the source with the component owning it, e.g. for [Registry::to_dot].
+    #[cfg(feature = "std")]
+    pub fn set_owner(&mut self, owner: &str) {
+        self.signal.heartbeat.label_source(owner)
+    }
+
+    /// Returns the owner label of the source, if it has been set.
+    #[cfg(feature = "std")]
+    pub fn owner(&self) -> Option<String> {
+        self.signal.heartbeat.source_owner()
+    }
+
+    /// Returns the statistics of the signal, summed over all sinks.
+    #[cfg(feature = "metrics")]
+    pub fn stats(&self) -> stats::Stats {
+        self.signal.heartbeat.counters().stats()
+    }
+}
+
+impl<T:Send + Default> Drop for Source<T> {
+    fn drop(&mut self) {
+        self.signal.heartbeat.stop()
     }
 }
 
@@ -0,0 +1,324 @@
+use core::{
+    cell::UnsafeCell, mem::MaybeUninit,
+    sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering::{AcqRel, Acquire, Release, SeqCst}},
+};
+
+use crate::signal::sync::State;
+
+/// A signal without any heap allocation, which can live in a `static`.
+/// The value is kept in `N` slots, one of them is read by the sinks while the source writes
+/// another one. Every slot counts its readers, the source only writes a slot without readers and
+/// waits for the readers of a slot if all others are busy, so `N` >= 3 avoids back pressure onto
+/// the source for sinks reading the current value.
+/// Only one source can exist at a time, this is checked at runtime.
+///
+/// ```
+/// use atomx::signal::StaticSignal;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// enum Mode { Idle, Run }
+///
+/// static MODE: StaticSignal<Mode, 3> = StaticSignal::new(Mode::Idle);
+///
+/// let mut source = MODE.source().expect("only source");
+/// let sink = MODE.sink();
+/// source.send(&Mode::Run);
+/// assert_eq!(sink.receive(), Mode::Run);
+/// ```
+pub struct StaticSignal<T, const N: usize> {
+    slots: [Slot<T>; N],
+    current: AtomicUsize, // index of the slot read by the sinks
+    initialized: UnsafeCell<[bool; N]>, // only accessed by the source
+    generation: AtomicUsize,
+    source: AtomicBool,
+    sinks: AtomicU32,
+}
+
+struct Slot<T> {
+    value: UnsafeCell<MaybeUninit<T>>,
+    readers: AtomicU32,
+}
+
+impl<T> Slot<T> {
+    #[allow(clippy::declare_interior_mutable_const)] // only repeated to initialize the slots
+    const EMPTY: Self = Slot { value: UnsafeCell::new(MaybeUninit::uninit()), readers: AtomicU32::new(0) };
+}
+
+// Safety: - the value of a slot is only written by the single source while it has no readers
+//         - readers only read the current slot, after registering as reader
+unsafe impl<T: Send + Sync, const N: usize> Sync for StaticSignal<T, N> {}
+unsafe impl<T: Send, const N: usize> Send for StaticSignal<T, N> {}
+
+impl<T, const N: usize> StaticSignal<T, N> where T: Clone + Send + Sync {
+    /// Create a new signal with the given value, usable to initialize a `static`.
+    /// With `N = 2` the source waits until no sink reads the other slot, it yields meanwhile
+    /// with `std` and busy spins without, so a slow [Sink::process] stalls the source.
+    pub const fn new(value: T) -> Self {
+        assert!(N >= 2, "a static signal needs at least two slots");
+        let mut slots = [Slot::EMPTY; N];
+        slots[0] = Slot { value: UnsafeCell::new(MaybeUninit::new(value)), readers: AtomicU32::new(0) };
+        let mut initialized = [false; N];
+        initialized[0] = true;
+        StaticSignal {
+            slots,
+            current: AtomicUsize::new(0),
+            initialized: UnsafeCell::new(initialized),
+            generation: AtomicUsize::new(0),
+            source: AtomicBool::new(false),
+            sinks: AtomicU32::new(0),
+        }
+    }
+
+    /// Returns the source of the signal, or `None` if a source exists already.
+    /// Once the source is dropped, a new one can be taken.
+    pub fn source(&self) -> Option<Source<'_, T, N>> {
+        match self.source.compare_exchange(false, true, AcqRel, Acquire) {
+            Ok(_) => Some(Source { signal: self }),
+            Err(_) => None,
+        }
+    }
+
+    /// Return a new sink of the signal.
+    pub fn sink(&self) -> Sink<'_, T, N> {
+        self.sinks.fetch_add(1, AcqRel);
+        Sink { signal: self, last_generation: AtomicUsize::new(usize::MAX) }
+    }
+
+    /// Returns the generation of the signal, which is the number of publishes so far.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Acquire) as u64
+    }
+
+    /// Access the current value, returns its generation.
+    fn process(&self, closure: &mut dyn FnMut(&T)) -> usize {
+        loop {
+            let generation = self.generation.load(SeqCst);
+            let index = self.current.load(SeqCst);
+            let slot = &self.slots[index];
+            slot.readers.fetch_add(1, SeqCst);
+            // the slot might have been replaced meanwhile and picked by the source to be written
+            if self.current.load(SeqCst) == index {
+                // Safety: - the current slot is always initialized
+                //         - the source does not write a slot with readers
+                closure(unsafe { (*slot.value.get()).assume_init_ref() });
+                slot.readers.fetch_sub(1, SeqCst);
+                return generation
+            }
+            slot.readers.fetch_sub(1, SeqCst);
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for StaticSignal<T, N> {
+    fn drop(&mut self) {
+        let initialized = self.initialized.get_mut();
+        for (slot, _) in self.slots.iter_mut().zip(initialized.iter()).filter(|(_, initialized)| **initialized) {
+            // Safety: the slot has been written
+            unsafe { slot.value.get_mut().assume_init_drop() }
+        }
+    }
+}
+
+/// The single writing end of a [StaticSignal].
+pub struct Source<'a, T, const N: usize> where T: Clone + Send + Sync {
+    signal: &'a StaticSignal<T, N>,
+}
+
+impl<T, const N: usize> Source<'_, T, N> where T: Clone + Send + Sync {
+    /// Publish the data to the sinks.
+    pub fn send(&mut self, data: &T) -> State {
+        self.write(&mut |slot| match slot {
+            Some(slot) => { slot.clone_from(data); None }
+            None => Some(data.clone()),
+        })
+    }
+
+    /// Modify a copy of the current data and publish it to the sinks.
+    pub fn modify(&mut self, closure: &mut dyn FnMut(&mut T)) -> State {
+        let signal = self.signal;
+        // Safety: the current slot is initialized and only the source writes slots
+        let current = unsafe { (*signal.slots[signal.current.load(Acquire)].value.get()).assume_init_ref() };
+        self.write(&mut |slot| match slot {
+            Some(slot) => {
+                slot.clone_from(current);
+                closure(slot);
+                None
+            }
+            None => {
+                let mut value = current.clone();
+                closure(&mut value);
+                Some(value)
+            }
+        })
+    }
+
+    /// Returns the number of current sinks of the signal.
+    pub fn sink_count(&self) -> u32 {
+        self.signal.sinks.load(Acquire)
+    }
+
+    /// Returns the generation of the signal, which is the number of publishes so far.
+    pub fn generation(&self) -> u64 {
+        self.signal.generation()
+    }
+
+    /// Write into a slot without readers and make it the current one. The closure is called with
+    /// the slot to write, or with `None` for an uninitialized slot and returns its value then.
+    fn write(&mut self, closure: &mut dyn FnMut(Option<&mut T>) -> Option<T>) -> State {
+        let signal = self.signal;
+        let current = signal.current.load(Acquire);
+        let mut index = current;
+        let slot = loop {
+            index = (index + 1) % N;
+            if index == current {
+                // all other slots are read, wait for their readers
+                #[cfg(feature = "std")]
+                std::thread::yield_now();
+                #[cfg(not(feature = "std"))]
+                core::hint::spin_loop();
+                continue
+            }
+            let slot = &signal.slots[index];
+            if slot.readers.load(SeqCst) == 0 {
+                break slot
+            }
+        };
+        // Safety: - only the source accesses initialized
+        //         - the slot is not current and has no readers, new readers back off
+        unsafe {
+            let initialized = &mut *signal.initialized.get();
+            let value = &mut *slot.value.get();
+            if initialized[index] {
+                closure(Some(value.assume_init_mut()));
+            } else {
+                value.write(closure(None).expect("value of an uninitialized slot"));
+                initialized[index] = true;
+            }
+        }
+        signal.current.store(index, SeqCst);
+        signal.generation.fetch_add(1, SeqCst);
+        match self.sink_count() {
+            0 => State::AllGone,
+            _ => State::Ready
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for Source<'_, T, N> where T: Clone + Send + Sync {
+    fn drop(&mut self) {
+        self.signal.source.store(false, Release)
+    }
+}
+
+/// A reading end of a [StaticSignal].
+pub struct Sink<'a, T, const N: usize> where T: Clone + Send + Sync {
+    signal: &'a StaticSignal<T, N>,
+    last_generation: AtomicUsize,
+}
+
+impl<T, const N: usize> Sink<'_, T, N> where T: Clone + Send + Sync {
+    /// Returns a copy of the received signal value.
+    pub fn receive(&self) -> T {
+        let mut value = None;
+        self.process(&mut |current| value = Some(current.clone()));
+        value.expect("processed")
+    }
+
+    /// Access the received signal value by reference, creates back pressure onto the source if
+    /// processing takes long and all other slots are read as well.
+    pub fn process(&self, closure: &mut dyn FnMut(&T)) {
+        let generation = self.signal.process(closure);
+        self.last_generation.store(generation, Release)
+    }
+
+    /// Check if source has changed the signal, since last read.
+    pub fn changed(&self) -> bool {
+        self.signal.generation.load(Acquire) != self.last_generation.load(Acquire)
+    }
+
+    /// Check if a source exists.
+    pub fn is_connected(&self) -> bool {
+        self.signal.source.load(Acquire)
+    }
+}
+
+impl<T, const N: usize> Clone for Sink<'_, T, N> where T: Clone + Send + Sync {
+    fn clone(&self) -> Self {
+        self.signal.sink()
+    }
+}
+
+impl<T, const N: usize> Drop for Sink<'_, T, N> where T: Clone + Send + Sync {
+    fn drop(&mut self) {
+        self.signal.sinks.fetch_sub(1, AcqRel);
+    }
+}
+
+
+#[cfg(test)]
+static COUNTER: StaticSignal<u32, 2> = StaticSignal::new(0);
+
+#[test]
+fn static_signal_publishes_to_sinks() {
+    let mut source = COUNTER.source().expect("first source");
+    assert!(COUNTER.source().is_none());
+    let sink = COUNTER.sink();
+    assert!(sink.changed());
+    assert_eq!(sink.receive(), 0);
+    assert!(!sink.changed());
+    assert_eq!(source.send(&1), State::Ready);
+    source.modify(&mut |value| *value += 1);
+    assert!(sink.changed());
+    assert_eq!(sink.receive(), 2);
+    assert_eq!(COUNTER.generation(), 2);
+    drop(source);
+    assert!(!sink.is_connected());
+    assert!(COUNTER.source().is_some());
+}
+
+#[test]
+fn static_signal_drops_initialized_slots() {
+    use std::sync::Arc;
+    let value = Arc::new(());
+    let signal = StaticSignal::<Arc<()>, 3>::new(value.clone());
+    signal.source().expect("source").send(&value);
+    assert_eq!(Arc::strong_count(&value), 3);
+    drop(signal);
+    assert_eq!(Arc::strong_count(&value), 1);
+}
+
+#[test]
+fn static_signal_is_consistent_under_concurrent_reads() {
+    static PAIR: StaticSignal<(u64, u64), 3> = StaticSignal::new((0, 0));
+    let reader = std::thread::spawn(|| {
+        let sink = PAIR.sink();
+        for _ in 0..10_000 {
+            let (a, b) = sink.receive();
+            assert_eq!(a, b);
+        }
+    });
+    let mut source = PAIR.source().expect("source");
+    for i in 0..10_000 {
+        source.send(&(i, i));
+    }
+    reader.join().expect("consistent reads");
+}
+
+#[test]
+fn static_signal_clones_once_into_uninitialized_slots() {
+    static CLONES: AtomicUsize = AtomicUsize::new(0);
+    struct Counted(u32);
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            CLONES.fetch_add(1, SeqCst);
+            Counted(self.0)
+        }
+    }
+    let signal = StaticSignal::<Counted, 3>::new(Counted(0));
+    let mut source = signal.source().expect("source");
+    source.modify(&mut |value| value.0 += 1);
+    assert_eq!(CLONES.load(SeqCst), 1);
+    source.send(&Counted(5));
+    assert_eq!(CLONES.load(SeqCst), 2);
+    assert_eq!(signal.sink().receive().0, 5);
+}
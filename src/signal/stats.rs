@@ -0,0 +1,141 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering::Relaxed},
+    time::{Duration, Instant},
+};
+
+/// Statistics of a signal, or of a single sink of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    /// Number of publishes of the source.
+    pub publishes: u64,
+    /// Number of reads, of all sinks for a signal or of the sink itself.
+    pub reads: u64,
+    /// Number of publishes never read, because another one was published before the next read.
+    pub skipped: u64,
+    /// Number of publishes rejected with [crate::signal::sync::State::Receiving].
+    pub rejections: u64,
+    /// Time spent inside the closures passed to `process`.
+    pub process_time: Duration,
+}
+
+impl Stats {
+    /// Export the statistics to the `metrics` facade, labeled with the given signal name.
+    /// Counters are set to their absolute values, so exporting repeatedly is fine.
+    pub fn export(&self, name: &str) {
+        let name = name.to_owned();
+        metrics::counter!("atomx_signal_publishes", "signal" => name.clone()).absolute(self.publishes);
+        metrics::counter!("atomx_signal_reads", "signal" => name.clone()).absolute(self.reads);
+        metrics::counter!("atomx_signal_skipped", "signal" => name.clone()).absolute(self.skipped);
+        metrics::counter!("atomx_signal_rejections", "signal" => name.clone()).absolute(self.rejections);
+        metrics::gauge!("atomx_signal_process_seconds", "signal" => name).set(self.process_time.as_secs_f64());
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Counters {
+    publishes: AtomicU64,
+    reads: AtomicU64,
+    skipped: AtomicU64,
+    rejections: AtomicU64,
+    process_nanos: AtomicU64,
+}
+
+impl Counters {
+    pub(crate) fn publish(&self) {
+        self.publishes.fetch_add(1, Relaxed);
+    }
+
+    pub(crate) fn reject(&self) {
+        self.rejections.fetch_add(1, Relaxed);
+    }
+
+    fn read(&self, skipped: u64, processed: Duration) {
+        self.reads.fetch_add(1, Relaxed);
+        self.skipped.fetch_add(skipped, Relaxed);
+        self.process_nanos.fetch_add(processed.as_nanos() as u64, Relaxed);
+    }
+
+    pub(crate) fn stats(&self) -> Stats {
+        Stats {
+            publishes: self.publishes.load(Relaxed),
+            reads: self.reads.load(Relaxed),
+            skipped: self.skipped.load(Relaxed),
+            rejections: self.rejections.load(Relaxed),
+            process_time: Duration::from_nanos(self.process_nanos.load(Relaxed)),
+        }
+    }
+}
+
+/// Counters of a single sink, skipped publishes are detected by gaps in the generation.
+pub(crate) struct SinkCounters {
+    counters: Counters,
+    last_generation: AtomicU64,
+}
+
+impl Default for SinkCounters {
+    fn default() -> Self {
+        SinkCounters { counters: Counters::default(), last_generation: AtomicU64::new(u64::MAX) }
+    }
+}
+
+impl SinkCounters {
+    /// Count a read of the given generation for the sink and the signal.
+    pub(crate) fn read(&self, signal: &Counters, generation: impl Into<u64>, processed: Duration) {
+        let generation = generation.into();
+        let skipped = match self.last_generation.swap(generation, Relaxed) {
+            u64::MAX => 0, // publishes before the first read are not missed by the sink
+            last => generation.saturating_sub(last).saturating_sub(1),
+        };
+        self.counters.read(skipped, processed);
+        signal.read(skipped, processed);
+    }
+
+    /// Returns the statistics of the sink, publishes and rejections are the ones of the signal.
+    pub(crate) fn stats(&self, signal: &Counters) -> Stats {
+        let signal = signal.stats();
+        Stats { publishes: signal.publishes, rejections: signal.rejections, ..self.counters.stats() }
+    }
+}
+
+/// Call the closure and return the time it took.
+pub(crate) fn timed(closure: impl FnOnce()) -> Duration {
+    let start = Instant::now();
+    closure();
+    start.elapsed()
+}
+
+
+#[test]
+fn sink_counts_skipped_generations() {
+    let signal = Counters::default();
+    let sink = SinkCounters::default();
+    signal.publish();
+    sink.read(&signal, 1u64, Duration::ZERO);
+    sink.read(&signal, 1u64, Duration::ZERO);
+    sink.read(&signal, 4u64, Duration::from_millis(1));
+    let stats = sink.stats(&signal);
+    assert_eq!(stats.reads, 3);
+    assert_eq!(stats.skipped, 2);
+    assert_eq!(stats.publishes, 1);
+    assert_eq!(stats.process_time, Duration::from_millis(1));
+}
+
+#[test]
+fn signal_counts_reads_skips_and_rejections() {
+    let (mut source, sink) = super::create::<u32>();
+    source.send(&1);
+    sink.receive();
+    source.send(&2);
+    source.send(&3);
+    sink.process(&mut |_| {});
+    assert_eq!(sink.stats(), Stats { publishes: 3, reads: 2, skipped: 1, ..sink.stats() });
+    assert_eq!(source.stats().reads, 2);
+
+    let mut source = super::sync::Source::from(0u32);
+    let sink = source.sink();
+    source.send(&1);
+    source.send(&2);
+    sink.receive();
+    assert_eq!(source.stats().rejections, 1);
+    assert_eq!(sink.stats().reads, 1);
+}
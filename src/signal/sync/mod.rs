@@ -4,7 +4,8 @@ pub mod source;
 pub use source::Source;
 pub use sink::Sink;
 
-use crate::signal::loom::{Arc, atomic::AtomicU32};
+use core::sync::atomic::Ordering;
+use crate::signal::{Heartbeat, loom::{Arc, atomic::{AtomicBool, AtomicU32}}};
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum State {
@@ -22,12 +23,72 @@ pub enum State {
 pub fn create<T>() -> (Source<T>, Sink<T>) where T: Send + Sync + Clone + Default {
     let source = Source {
         inner: crate::signal::Source::from(T::default()),
-        acks: Arc::new(AtomicU32::new(0)),
+        handshake: Arc::new(Handshake::new(0)),
     };
     let sink = Sink::from(&source);
     (source, sink)
 }
 
+/// The handshake between a synced source and its sinks.
+/// The source publishes while holding the lock, and sinks join and leave while holding it, so
+/// the acknowledges of a publish are counted for exactly the sinks existing at that time. A sink
+/// joining later acknowledges from the next publish on, a sink leaving takes its pending
+/// acknowledge with it. Sinks can not join or leave within the closure of a modify.
+pub(in crate::signal) struct Handshake {
+    acks: AtomicU32, // pending acknowledges of the last publish, u32::MAX if never sent
+    locked: AtomicBool,
+}
+
+impl Handshake {
+    fn new(acks: u32) -> Self {
+        Handshake { acks: AtomicU32::new(acks), locked: AtomicBool::new(false) }
+    }
+
+    /// Run the closure while holding the lock, the lock is released even if the closure panics.
+    fn lock<R>(&self, closure: impl FnOnce() -> R) -> R {
+        struct Unlock<'a>(&'a AtomicBool);
+        impl Drop for Unlock<'_> {
+            fn drop(&mut self) {
+                self.0.store(false, Ordering::Release)
+            }
+        }
+        while self.locked.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            #[cfg(feature = "std")]
+            crate::signal::loom::thread::yield_now();
+            #[cfg(not(feature = "std"))]
+            crate::signal::loom::hint::spin_loop();
+        }
+        let _unlock = Unlock(&self.locked);
+        closure()
+    }
+
+    /// Returns the number of pending acknowledges, u32::MAX if never sent.
+    pub(in crate::signal) fn pending(&self) -> u32 {
+        self.acks.load(Ordering::Acquire)
+    }
+
+    fn reset(&self, acks: u32) {
+        self.acks.store(acks, Ordering::Release)
+    }
+
+    /// Acknowledge the last publish for one sink.
+    fn acknowledge(&self, _heartbeat: &Heartbeat) {
+        let _pending = self.acks.fetch_sub(1, Ordering::AcqRel);
+        debug_assert!(_pending != 0 && _pending != u32::MAX, "acknowledged a publish twice");
+        #[cfg(feature = "tracing")]
+        if _pending == 1 {
+            crate::signal::trace::acknowledged(_heartbeat);
+        }
+    }
+}
+
+impl Default for Handshake {
+    /// The handshake of a default constructed sink, which has never been sent to.
+    fn default() -> Self {
+        Handshake::new(u32::MAX)
+    }
+}
+
 #[test]
 fn changed_is_true_if_create_synced() {
     let (_, snk) = crate::signal::sync::create::<f32>();
@@ -61,6 +122,84 @@ fn sync_sink_is_not_connected_after_source_dropped() {
     assert!(!sink.is_connected(), "Sync sink should not be connected after source is dropped");
 }
 
+#[test]
+fn failed_modify_keeps_handshake() {
+    let (mut src, snk) = crate::signal::sync::create::<u32>();
+    assert_eq!(src.send(&1), State::Ready);
+    assert_eq!(src.try_modify(&mut |_| Err(())), Ok(State::Receiving));
+    assert_eq!(snk.receive(), 1);
+    assert_eq!(src.try_modify(&mut |value| { *value = 2; Err(()) }), Err(()));
+    assert!(!snk.changed());
+    assert_eq!(src.try_modify(&mut |value| { *value += 1; Ok::<_, ()>(()) }), Ok(State::Ready));
+    assert_eq!(snk.receive(), 2);
+
+    let mut src = Source::from(0);
+    let _snk = src.sink();
+    assert!(!src.equals_last(&0), "never sent");
+    assert_eq!(src.try_modify(&mut |_| Err(())), Err(()));
+    assert!(!src.equals_last(&0), "still never sent");
+}
+
+#[test]
+fn sink_joining_during_cycle_does_not_acknowledge() {
+    let (mut src, a) = crate::signal::sync::create::<u32>();
+    a.receive();
+    assert_eq!(src.send(&1), State::Ready);
+    let b = src.sink();
+    assert_eq!(b.receive(), 1);
+    assert_eq!(src.send(&2), State::Receiving, "a has not read 1 yet");
+    assert!(a.changed());
+    assert_eq!(a.receive(), 1);
+    assert_eq!(src.send(&2), State::Ready);
+    assert!(a.changed() && b.changed());
+    assert_eq!(src.send(&3), State::Receiving, "b is counted from its second publish on");
+}
+
+#[test]
+fn dropped_sink_releases_the_source() {
+    let (mut src, a) = crate::signal::sync::create::<u32>();
+    let b = a.clone();
+    assert_eq!(src.send(&1), State::Ready);
+    a.receive();
+    assert_eq!(src.send(&2), State::Receiving);
+    drop(b);
+    assert_eq!(src.send(&2), State::Ready);
+    drop(a);
+    assert_eq!(src.send(&3), State::AllGone);
+}
+
+#[test]
+fn clone_is_a_new_sink() {
+    let (mut src, a) = crate::signal::sync::create::<u32>();
+    assert_eq!(src.send(&1), State::Ready);
+    a.receive();
+    let b = a.clone();
+    assert!(b.changed(), "all data is new to a sink");
+    assert_eq!(b.receive(), 1);
+    assert_eq!(src.send(&2), State::Ready, "b has not been counted for 1");
+    b.receive();
+    assert_eq!(src.send(&3), State::Receiving, "a has not read 2 yet");
+}
+
+#[test]
+fn pending_acknowledges_count_sinks_of_the_publish() {
+    let (mut src, a) = crate::signal::sync::create::<u32>();
+    let never_sent = Source::from(0);
+    assert_eq!(never_sent.handshake.pending(), u32::MAX);
+    assert_eq!(src.send(&1), State::Ready);
+    assert_eq!(src.handshake.pending(), 1);
+    let clones: Vec<_> = (0..3).map(|_| a.clone()).collect();
+    clones.iter().for_each(|clone| { clone.receive(); });
+    assert_eq!(src.handshake.pending(), 1, "clones are not counted for the running publish");
+    a.receive();
+    a.receive();
+    assert_eq!(src.handshake.pending(), 0, "a sink acknowledges once");
+    assert_eq!(src.send(&2), State::Ready);
+    assert_eq!(src.handshake.pending(), 4);
+    drop(clones);
+    assert_eq!(src.handshake.pending(), 1);
+}
+
 #[test]
 #[ignore = "only show sizes"]
 fn sizes() {
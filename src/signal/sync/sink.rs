@@ -1,15 +1,22 @@
-use std::sync::atomic::{Ordering, AtomicU64};
+#[cfg(feature = "std")]
+use alloc::string::String;
+use core::sync::atomic::Ordering;
 use crate::signal::{
-    Signal,
-    loom::{Arc, atomic::AtomicU32}
+    Signal, Heartbeat,
+    loom::{Arc, AtomicGeneration, Generation, atomic::AtomicBool}
 };
-use super::source::Source;
+use super::{Handshake, source::Source};
 
 #[derive(Default)]
 pub struct Sink<T> where T: Clone + Sync + Send + Default {
     signal: Arc<Signal<T>>,
-    acks: Arc<AtomicU32>,
-    last_id: AtomicU64,
+    handshake: Arc<Handshake>,
+    last_generation: AtomicGeneration, // the generation acknowledged last, or of joining
+    received: AtomicBool,
+    #[cfg(feature = "std")]
+    owner: Option<String>,
+    #[cfg(feature = "metrics")]
+    counters: crate::signal::stats::SinkCounters,
 }
 
 impl<T> Sink<T>  where T: Clone + Sync + Send + Default {
@@ -17,10 +24,24 @@ impl<T> Sink<T>  where T: Clone + Sync + Send + Default {
     /// This is the only way to create a Sink. That's necessary to guaranty that both share the
     /// same sync property.
     pub fn from(source: &Source<T>) -> Self {
+        Sink::join(source.inner.signal(), source.handshake.clone())
+    }
+
+    /// Connect a new sink, which acknowledges from the next publish on. All data is new to it.
+    fn join(signal: Arc<Signal<T>>, handshake: Arc<Handshake>) -> Self {
+        let generation = handshake.lock(|| {
+            signal.heartbeat.connect();
+            signal.heartbeat.raw_generation()
+        });
         Sink {
-            signal: source.inner.signal(),
-            acks: source.acks.clone(),
-            last_id: AtomicU64::new(0),
+            signal,
+            handshake,
+            last_generation: AtomicGeneration::new(generation),
+            received: AtomicBool::new(false),
+            #[cfg(feature = "std")]
+            owner: None,
+            #[cfg(feature = "metrics")]
+            counters: Default::default(),
         }
     }
 
@@ -28,8 +49,11 @@ impl<T> Sink<T>  where T: Clone + Sync + Send + Default {
     /// This is especially useful for small or primitive types. If the signal data is to expansive
     /// to copy have a look at [process].
     pub fn receive(&self) -> T {
-        let (value, id) = self.signal.value();
-        self.acknowledge(id);
+        let generation = self.signal.heartbeat.raw_generation();
+        #[cfg(feature = "metrics")]
+        self.counters.read(self.signal.heartbeat.counters(), generation, Default::default());
+        let (value, _) = self.signal.value();
+        self.acknowledge(generation);
         value
     }
 
@@ -38,13 +62,23 @@ impl<T> Sink<T>  where T: Clone + Sync + Send + Default {
     /// creates back pressure onto the sender if processing takes to much time (even if not
     /// synced).
     pub fn process(&self, closure: &mut dyn FnMut(&T)) {
-        let id = self.signal.process(closure);
-        self.acknowledge(id)
+        let generation = self.signal.heartbeat.raw_generation();
+        #[cfg(not(feature = "metrics"))]
+        self.signal.process(closure);
+        #[cfg(feature = "metrics")]
+        {
+            let processed = crate::signal::stats::timed(|| { self.signal.process(closure); });
+            self.counters.read(self.signal.heartbeat.counters(), generation, processed);
+        }
+        self.acknowledge(generation)
     }
 
     /// Check if source have changed the signal, since last acknowledge.
+    /// The generation is compared, so a sink which has not received anything yet sees the
+    /// signal as changed.
     pub fn changed(&self) -> bool {
-        self.signal.box_id() != self.last_id.load(Ordering::Acquire)
+        !self.received.load(Ordering::Acquire)
+            || self.signal.heartbeat.raw_generation() != self.last_generation.load(Ordering::Acquire)
     }
 
     /// Check if sink is connected.
@@ -52,22 +86,68 @@ impl<T> Sink<T>  where T: Clone + Sync + Send + Default {
         Arc::strong_count(&self.signal) > 1
     }
 
+    /// Returns the heartbeat of the signal, to observe the liveness of the source.
+    /// The heartbeat does not take part in the handshake.
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.signal.heartbeat.clone()
+    }
+
+    /// Label the sink with the component owning it, e.g. for [crate::signal::Registry::to_dot].
+    #[cfg(feature = "std")]
+    pub fn set_owner(&mut self, owner: &str) {
+        self.signal.heartbeat.relabel_sink(self.owner.as_deref(), Some(owner));
+        self.owner = Some(owner.to_owned());
+    }
+
+    /// Returns the owner label of the sink, if it has been set.
+    #[cfg(feature = "std")]
+    pub fn owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+
+    /// Returns the statistics of this sink.
+    #[cfg(feature = "metrics")]
+    pub fn stats(&self) -> crate::signal::stats::Stats {
+        self.counters.stats(self.signal.heartbeat.counters())
+    }
+
     /// When the signal is synced, this is used to inform the Sender that the Sink has been received
-    /// the signal.
-    fn acknowledge(&self, id: u64) {
-        if self.last_id.load(Ordering::Acquire) != id {
-            self.acks.fetch_sub(1, Ordering::AcqRel);
+    /// the signal. The generation is the one loaded before reading the value.
+    /// A sink is counted for every publish after it joined and can not fall behind by more than
+    /// that publish, so only the generation following the last one is acknowledged.
+    fn acknowledge(&self, generation: Generation) {
+        let next = |last: Generation| (last.wrapping_add(1) == generation).then_some(generation);
+        if self.last_generation.fetch_update(Ordering::AcqRel, Ordering::Acquire, next).is_ok() {
+            self.handshake.acknowledge(&self.signal.heartbeat);
         }
-        self.last_id.store(id, Ordering::Release)
+        self.received.store(true, Ordering::Release)
     }
 }
 
 impl<T> Clone for Sink<T> where T: Clone + Sync + Send + Default {
+    /// The clone is a new sink, see [Sink::from].
     fn clone(&self) -> Self {
-        Self {
-            signal: self.signal.clone(),
-            acks: self.acks.clone(),
-            last_id: AtomicU64::default()
+        #[allow(unused_mut)] // only labeled with std
+        let mut sink = Sink::join(self.signal.clone(), self.handshake.clone());
+        #[cfg(feature = "std")]
+        if let Some(owner) = &self.owner {
+            sink.set_owner(owner);
         }
+        sink
+    }
+}
+
+impl<T> Drop for Sink<T> where T: Clone + Sync + Send + Default {
+    /// A sink which has not acknowledged the last publish, does so when leaving.
+    fn drop(&mut self) {
+        let (handshake, heartbeat) = (&self.handshake, &self.signal.heartbeat);
+        handshake.lock(|| {
+            if self.last_generation.load(Ordering::Acquire) != heartbeat.raw_generation() {
+                handshake.acknowledge(heartbeat);
+            }
+            heartbeat.disconnect();
+        });
+        #[cfg(feature = "std")]
+        heartbeat.relabel_sink(self.owner.as_deref(), None)
     }
 }
@@ -1,15 +1,17 @@
-use std::sync::atomic::Ordering;
+#[cfg(feature = "std")]
+use alloc::string::String;
 
 use crate::signal::{
-    loom::{Arc, atomic::AtomicU32}
+    Heartbeat,
+    loom::Arc
 };
 use super::{
-    State, Sink
+    Handshake, State, Sink
 };
 
 pub struct Source<T> where T: Clone + Sync + Send + Default {
     pub (super) inner: crate::signal::Source<T>,
-    pub (super) acks: Arc<AtomicU32>,
+    pub (in crate::signal) handshake: Arc<Handshake>,
 }
 
 impl<T> Source<T> where T: Clone + Sync + Send + Default {
@@ -17,7 +19,7 @@ impl<T> Source<T> where T: Clone + Sync + Send + Default {
     pub fn from(value: T) -> Self {
         Source {
             inner: crate::signal::Source::from(value),
-            acks: Arc::new(AtomicU32::new(u32::MAX)),
+            handshake: Arc::new(Handshake::default()),
         }
     }
 
@@ -25,20 +27,47 @@ impl<T> Source<T> where T: Clone + Sync + Send + Default {
         Sink::from(self)
     }
 
+    /// Check if all sinks have acknowledged the last publish, without changing the handshake.
     fn try_sync(&self) -> State {
         use State::*;
         match self.sink_count() {
             1.. => match self.acks_count() {
-                0 | u32::MAX => {
-                    self.reset_acks(self.sink_count());
-                    Ready
+                0 | u32::MAX => Ready,
+                _pending => {
+                    #[cfg(feature = "metrics")]
+                    self.inner.signal.heartbeat.counters().reject();
+                    #[cfg(feature = "tracing")]
+                    crate::signal::trace::rejected(&self.inner.signal.heartbeat, _pending);
+                    Receiving
                 }
-                _ => Receiving,
             },
             0   => AllGone
         }
     }
 
+    /// Returns the heartbeat of the signal, which tracks the publishes of this source.
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.inner.heartbeat()
+    }
+
+    /// Label the source with the component owning it, e.g. for [crate::signal::Registry::to_dot].
+    #[cfg(feature = "std")]
+    pub fn set_owner(&mut self, owner: &str) {
+        self.inner.set_owner(owner)
+    }
+
+    /// Returns the owner label of the source, if it has been set.
+    #[cfg(feature = "std")]
+    pub fn owner(&self) -> Option<String> {
+        self.inner.owner()
+    }
+
+    /// Returns the statistics of the signal.
+    #[cfg(feature = "metrics")]
+    pub fn stats(&self) -> crate::signal::stats::Stats {
+        self.inner.stats()
+    }
+
     pub fn sink_count(&self) -> u32 {
         // the expectation here is, that this count does not change often
         Arc::strong_count(&self.inner.signal) as u32 -1
@@ -48,7 +77,7 @@ impl<T> Source<T> where T: Clone + Sync + Send + Default {
         use State::*;
         let state = self.try_sync();
         if state == Ready {
-            self.inner.send(signal);
+            self.publish(&mut |inner| { inner.send(signal); true });
         }
         state
     }
@@ -57,25 +86,77 @@ impl<T> Source<T> where T: Clone + Sync + Send + Default {
         use State::*;
         let state = self.try_sync();
         if state == Ready {
-            self.inner.memory.write_in_place(closure);
-            self.inner.signal.swap(&mut self.inner.memory);
+            self.publish(&mut |inner| { inner.modify(closure); true });
         }
         state
     }
 
-    fn reset_acks(&self, acks: u32) {
-        self.acks.store(acks, Ordering::Release)
+    /// Like [Source::modify], but the data is only published if the closure succeeds, see
+    /// [crate::signal::Source::try_modify]. On an error the handshake is left as it was.
+    pub fn try_modify<E>(&mut self, closure: &mut dyn FnMut(&mut T) -> Result<(), E>) -> Result<State, E> {
+        use State::*;
+        let state = self.try_sync();
+        if state == Ready {
+            let mut result = Ok(state);
+            self.publish(&mut |inner| match inner.try_modify(closure) {
+                Ok(_) => true,
+                Err(error) => { result = Err(error); false }
+            });
+            return result
+        }
+        Ok(state)
+    }
+
+    /// Apply the patch to the current data and publish it, if all sinks have acknowledged the
+    /// last publish, see [crate::signal::Source::apply].
+    pub fn apply(&mut self, patch: T::Patch) -> State
+        where T: crate::signal::Patchable, T::Patch: Send + Sync + 'static
+    {
+        use State::*;
+        let state = self.try_sync();
+        if state == Ready {
+            let mut patch = Some(patch);
+            self.publish(&mut |inner| { inner.apply(patch.take().expect("applied once")); true });
+        }
+        state
+    }
+
+    /// Let [Source::modify] start from the last published data, see
+    /// [crate::signal::Source::copy_forward].
+    pub fn copy_forward(&mut self) {
+        self.inner.copy_forward()
+    }
+
+    /// Let [Source::modify] start from the last published data, see
+    /// [crate::signal::Source::sync_forward].
+    pub fn sync_forward(&mut self) where T: crate::signal::SyncFrom {
+        self.inner.sync_forward()
+    }
+
+    /// Publish through the closure, which returns if it has published. The acknowledges are
+    /// counted for the sinks existing meanwhile, if nothing is published the handshake is left as
+    /// it was, e.g. a source which never sent stays so.
+    fn publish(&mut self, closure: &mut dyn FnMut(&mut crate::signal::Source<T>) -> bool) {
+        let Source { inner, handshake } = self;
+        handshake.lock(|| {
+            let acks = handshake.pending();
+            // acknowledges of the new publish may arrive as soon as it is visible
+            handshake.reset(inner.signal.heartbeat.sink_count());
+            if !closure(inner) {
+                handshake.reset(acks);
+            }
+        })
     }
 
     fn acks_count(&self) -> u32 {
-        self.acks.load(Ordering::Acquire)
+        self.handshake.pending()
     }
 
     /// Check if the given data equals the last published data.
     /// Returns false when waiting for acknowledgements or when never sent yet,
     /// to ensure the sync state machine can progress.
     pub fn equals_last(&self, data: &T) -> bool where T: PartialEq {
-        // Check state without side effects (try_sync resets acks)
+        // try_sync reports never sent as Ready, so the acknowledges are checked directly
         // acks == u32::MAX means never sent, acks == 0 means all acked and ready
         match self.sink_count() {
             0 => true,  // AllGone: no receivers, skip sending
@@ -87,3 +168,27 @@ impl<T> Source<T> where T: Clone + Sync + Send + Default {
     }
 
 }
+
+#[cfg(feature = "serde")]
+impl<T> Source<T> where T: Clone + Sync + Send + Default {
+    /// Create a new source from a snapshot, see [crate::signal::Source::restore].
+    pub fn restore(snapshot: crate::signal::snapshot::Snapshot<T>) -> Self {
+        Source {
+            inner: crate::signal::Source::restore(snapshot),
+            handshake: Arc::new(Handshake::default()),
+        }
+    }
+
+    /// Returns a snapshot of the last published value.
+    pub fn snapshot(&self) -> crate::signal::snapshot::Snapshot<T> {
+        self.inner.snapshot()
+    }
+}
+
+/// A source is serialized as snapshot of the last published value, without copying it.
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for Source<T> where T: serde::Serialize + Clone + Sync + Send + Default {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.inner.serialize(serializer)
+    }
+}
@@ -0,0 +1,121 @@
+use tracing::Span;
+
+use super::{Heartbeat, Registry, sync::State};
+
+/// The name of the signal as registered in a [Registry], empty if it is not registered.
+fn name(heartbeat: &Heartbeat) -> String {
+    heartbeat.name().unwrap_or_default()
+}
+
+pub(crate) fn send(heartbeat: &Heartbeat) -> Span {
+    tracing::trace_span!("send", signal = %name(heartbeat))
+}
+
+pub(crate) fn modify(heartbeat: &Heartbeat) -> Span {
+    tracing::trace_span!("modify", signal = %name(heartbeat))
+}
+
+pub(crate) fn published(heartbeat: &Heartbeat, state: &State) {
+    tracing::trace!(
+        generation = heartbeat.generation(), sinks = heartbeat.sink_count(), outcome = ?state,
+        "published"
+    );
+}
+
+/// A synced source could not publish, because sinks have not acknowledged the last value.
+/// The handshake is stalled, if it is pending longer than [Registry::STALL].
+pub(crate) fn rejected(heartbeat: &Heartbeat, pending: u32) {
+    match heartbeat.elapsed() > Registry::STALL {
+        true => tracing::warn!(signal = %name(heartbeat), pending, "sync handshake stalled"),
+        false => tracing::trace!(signal = %name(heartbeat), pending, "sync handshake pending"),
+    }
+}
+
+pub(crate) fn acknowledged(heartbeat: &Heartbeat) {
+    tracing::debug!(
+        signal = %name(heartbeat), generation = heartbeat.generation(), "sync handshake completed"
+    );
+}
+
+pub(crate) fn connected(heartbeat: &Heartbeat, sinks: u32) {
+    tracing::debug!(signal = %name(heartbeat), sinks, "sink connected");
+}
+
+pub(crate) fn disconnected(heartbeat: &Heartbeat, sinks: u32) {
+    tracing::debug!(signal = %name(heartbeat), sinks, "sink disconnected");
+}
+
+
+#[cfg(test)]
+#[derive(Default)]
+struct Capture {
+    lines: std::sync::Mutex<Vec<String>>,
+}
+
+#[cfg(test)]
+impl Capture {
+    fn push(&self, line: String) {
+        self.lines.lock().expect("not poisoned").push(line)
+    }
+}
+
+#[cfg(test)]
+struct Fields<'a>(&'a mut String);
+
+#[cfg(test)]
+impl tracing::field::Visit for Fields<'_> {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        use std::fmt::Write;
+        let _ = write!(self.0, " {}={:?}", field.name(), value);
+    }
+}
+
+#[cfg(test)]
+impl tracing::Subscriber for &'static Capture {
+    fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        let mut line = format!("span {}", span.metadata().name());
+        span.record(&mut Fields(&mut line));
+        self.push(line);
+        tracing::span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+    fn event(&self, event: &tracing::Event<'_>) {
+        let mut line = String::from("event");
+        event.record(&mut Fields(&mut line));
+        self.push(line);
+    }
+
+    fn enter(&self, _: &tracing::span::Id) {}
+
+    fn exit(&self, _: &tracing::span::Id) {}
+}
+
+#[test]
+fn signal_events_are_traced_with_name() {
+    let capture: &'static Capture = Box::leak(Box::default());
+    tracing::subscriber::with_default(capture, || {
+        let registry = Registry::new();
+        let mut source = super::sync::Source::from(0u32);
+        registry.register_sync("speed", &source);
+        let sink = source.sink();
+        source.send(&1);
+        source.send(&2);
+        sink.receive();
+        drop(sink);
+    });
+    let lines = capture.lines.lock().expect("not poisoned").join("\n");
+    assert!(lines.contains("event message=sink connected signal=speed sinks=1"), "{}", lines);
+    assert!(lines.contains("span send signal=speed"), "{}", lines);
+    assert!(lines.contains("event message=published generation=1 sinks=1 outcome=Ready"), "{}", lines);
+    assert!(lines.contains("event message=sync handshake pending signal=speed pending=1"), "{}", lines);
+    assert!(lines.contains("event message=sync handshake completed signal=speed generation=1"), "{}", lines);
+    assert!(lines.contains("event message=sink disconnected signal=speed sinks=0"), "{}", lines);
+}
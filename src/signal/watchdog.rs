@@ -0,0 +1,145 @@
+use std::time::{Duration, Instant};
+
+use super::{Heartbeat, Source, Sink};
+
+/// Liveness of a watched source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Status {
+    /// The source has published within the expected period.
+    #[default]
+    Alive,
+    /// The source has not published within the expected period.
+    Stalled,
+    /// The source has been dropped.
+    Gone,
+}
+
+/// A watchdog supervises the liveness of sources.
+/// Every watched signal is registered with the period in which its source is expected to
+/// publish. The watchdog itself does not run a thread, [Watchdog::check] needs to be called
+/// regularly, e.g. by a supervisor thread.
+#[derive(Default)]
+pub struct Watchdog {
+    watched: Vec<Watched>,
+}
+
+struct Watched {
+    heartbeat: Heartbeat,
+    period: Duration,
+    status: Source<Status>,
+}
+
+impl Watchdog {
+    pub fn new() -> Self {
+        Watchdog { watched: Vec::new() }
+    }
+
+    /// Watch a signal through its heartbeat, see [Sink::heartbeat].
+    /// Returns a sink of the status signal, which is published on each status change.
+    pub fn watch(&mut self, heartbeat: Heartbeat, period: Duration) -> Sink<Status> {
+        let status = Source::from(Status::Alive);
+        let sink = status.sink();
+        self.watched.push(Watched { heartbeat, period, status });
+        sink
+    }
+
+    /// Returns the number of watched signals.
+    pub fn len(&self) -> usize {
+        self.watched.len()
+    }
+
+    /// Check if no signal is watched.
+    pub fn is_empty(&self) -> bool {
+        self.watched.is_empty()
+    }
+
+    /// Returns the current status of the signal registered at the given index.
+    pub fn status(&self, index: usize) -> Option<Status> {
+        self.watched.get(index).map(|watched| watched.current(Instant::now()))
+    }
+
+    /// Check all watched signals. On each status change the closure is called with the index of
+    /// the signal in the order of registration and its new status. Afterwards the change is
+    /// published to the status signal.
+    /// Returns the number of signals which are not alive.
+    pub fn check(&mut self, closure: &mut dyn FnMut(usize, Status)) -> usize {
+        self.check_at(Instant::now(), closure)
+    }
+
+    /// Check all watched signals as of the given point in time, see [Watchdog::check].
+    /// This allows a supervisor with its own clock, e.g. a simulation, to drive the watchdog.
+    pub fn check_at(&mut self, now: Instant, closure: &mut dyn FnMut(usize, Status)) -> usize {
+        let mut failures = 0;
+        for (index, watched) in self.watched.iter_mut().enumerate() {
+            let status = watched.current(now);
+            if status != Status::Alive {
+                failures += 1;
+            }
+            if !watched.status.equals_last(&status) {
+                closure(index, status);
+                watched.status.send(&status);
+            }
+        }
+        failures
+    }
+}
+
+impl Watched {
+    fn current(&self, now: Instant) -> Status {
+        if !self.heartbeat.is_alive() {
+            Status::Gone
+        } else if now.saturating_duration_since(self.heartbeat.last()) > self.period {
+            Status::Stalled
+        } else {
+            Status::Alive
+        }
+    }
+}
+
+
+#[test]
+fn watchdog_reports_alive_source() {
+    let (mut source, sink) = super::create::<u32>();
+    let mut watchdog = Watchdog::new();
+    let status = watchdog.watch(sink.heartbeat(), Duration::from_secs(60));
+    source.send(&1);
+    let mut changes = 0;
+    assert_eq!(watchdog.check(&mut |_, _| changes += 1), 0);
+    assert_eq!(changes, 0);
+    assert_eq!(status.receive(), Status::Alive);
+}
+
+#[test]
+fn watchdog_reports_stalled_source() {
+    let (mut source, sink) = super::create::<u32>();
+    let mut watchdog = Watchdog::new();
+    let status = watchdog.watch(sink.heartbeat(), Duration::from_secs(1));
+    let late = sink.heartbeat().last() + Duration::from_secs(2);
+    let mut changes = vec![];
+    assert_eq!(watchdog.check_at(late, &mut |index, status| changes.push((index, status))), 1);
+    assert_eq!(changes, vec![(0, Status::Stalled)]);
+    assert_eq!(status.receive(), Status::Stalled);
+
+    source.send(&1);
+    assert_eq!(watchdog.check_at(sink.heartbeat().last(), &mut |_, _| {}), 0);
+    assert_eq!(status.receive(), Status::Alive);
+}
+
+#[test]
+fn watchdog_reports_dropped_source() {
+    let (source, sink) = super::sync::create::<u32>();
+    let mut watchdog = Watchdog::new();
+    let status = watchdog.watch(sink.heartbeat(), Duration::from_secs(60));
+    drop(source);
+    assert_eq!(watchdog.check(&mut |_, _| {}), 1);
+    assert_eq!(watchdog.status(0), Some(Status::Gone));
+    assert_eq!(status.receive(), Status::Gone);
+}
+
+#[test]
+fn heartbeat_does_not_count_as_sink() {
+    let (source, sink) = super::sync::create::<u32>();
+    let mut watchdog = Watchdog::new();
+    watchdog.watch(sink.heartbeat(), Duration::from_secs(60));
+    assert_eq!(source.sink_count(), 1);
+}
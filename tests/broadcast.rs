@@ -0,0 +1,46 @@
+#![cfg(feature = "std")]
+
+use atomx::broadcast::{self, Error};
+
+#[test]
+fn sinks_see_ordered_values_on_concurrent_access() {
+    let (mut source, sink) = broadcast::create::<u64>(8);
+    let mut handles = vec![];
+
+    // run the consumers
+    for _thread in 0..4 {
+        let mut sink = sink.clone();
+        handles.push(std::thread::spawn(move || {
+            let mut last = None;
+            let mut received = 0;
+            loop {
+                match sink.receive() {
+                    Ok(value) => {
+                        if let Some(last) = last {
+                            assert!(value > last, "values must be received in order");
+                        }
+                        last = Some(value);
+                        received += 1;
+                    }
+                    Err(Error::Lagged(missed)) => assert!(missed > 0),
+                    Err(Error::AllGone) => break,
+                    Err(Error::Empty) => unreachable!("receive waits for values"),
+                }
+            }
+            (last, received)
+        }));
+    }
+    drop(sink);
+
+    // run the producer
+    for value in 0..100_000 {
+        source.send(value);
+    }
+    drop(source);
+
+    for handle in handles {
+        let (last, received) = handle.join().expect("Couldn't join on the associated thread");
+        assert_eq!(last, Some(99_999));
+        assert!(received > 0);
+    }
+}
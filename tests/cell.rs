@@ -0,0 +1,43 @@
+#![cfg(feature = "std")]
+
+use atomx::cell::AtomicCell;
+
+#[test]
+fn no_update_is_lost_on_concurrent_access() {
+    let cell = AtomicCell::new(0u64);
+    let mut handles = vec![];
+
+    for _thread in 0..4 {
+        let cell = cell.clone();
+        handles.push(std::thread::spawn(move || {
+            for _ in 0..10_000 {
+                cell.update(&mut |value| value + 1);
+            }
+        }));
+    }
+
+    for handle in handles {
+        handle.join().expect("Couldn't join on the associated thread");
+    }
+    assert_eq!(cell.load(), 40_000);
+}
+
+#[test]
+fn readers_see_consistent_values_on_concurrent_access() {
+    let cell = AtomicCell::new(vec![0u64; 64]);
+    let reader = {
+        let cell = cell.clone();
+        std::thread::spawn(move || {
+            for _ in 0..10_000 {
+                cell.process(&mut |values| {
+                    assert!(values.iter().all(|value| *value == values[0]));
+                });
+            }
+        })
+    };
+
+    for round in 0..10_000 {
+        cell.store(vec![round; 64]);
+    }
+    reader.join().expect("Couldn't join on the reader thread");
+}
@@ -1,8 +1,12 @@
 #![cfg(loom)]
+//! Models of the signals under loom. Under loom the counted reclaimer is used instead of the hazard
+//! pointers of haphazard, which are not built on the atomics of loom, see `src/signal/reclaim.rs`.
+//! So the models explore the orderings of the counted reclaimer, the hazard pointer orderings are
+//! not covered.
 
 use atomx::signal;
 use atomx::signal::sync::State;
-use loom::thread;
+use loom::{sync::Arc, thread};
 
 #[test]
 fn loom_signal_ack(){
@@ -15,12 +19,11 @@ fn loom_signal_ack(){
         sender.send(&input1);
         assert_eq!(State::Receiving, sender.send(&input2));
 
-        // clock generator module
+        // both sinks acknowledge the first value concurrently
         let t1 = thread::spawn( move ||{
             assert_eq!(input1, sink1.receive());
         });
 
-        // clock consumer module
         let t2 = thread::spawn( move || {
             assert_eq!(input1, sink2.receive());
         });
@@ -37,3 +40,109 @@ fn loom_signal_ack(){
     });
 }
 
+
+#[test]
+fn loom_signal_swap_during_process(){
+    loom::model(|| {
+        let (mut source, sink) = signal::create::<(u32, u32)>();
+
+        // the slots are tracked by loom, a write to a slot which is read is reported
+        let reader = thread::spawn(move || {
+            let mut read = (0, 0);
+            sink.process(&mut |value| read = *value);
+            assert_eq!(read.0, read.1);
+            read.0
+        });
+
+        source.send(&(1, 1));
+        source.modify(&mut |value| *value = (2, 2));
+        assert!(reader.join().expect("completion") <= 2);
+    });
+}
+
+#[test]
+fn loom_signal_drop_retires_while_reading(){
+    loom::model(|| {
+        // loom reports the Arcs of values which are never reclaimed
+        let (mut source, sink) = signal::mp::create::<Option<Arc<u32>>>();
+        source.send(&Some(Arc::new(1)));
+
+        let reader = thread::spawn(move || {
+            let value = sink.receive();
+            drop(sink);
+            value
+        });
+
+        // retires the first value, while the reader may still hold it
+        source.send(&Some(Arc::new(2)));
+        drop(source);
+        let value = reader.join().expect("completion").expect("sent value");
+        assert!(*value == 1 || *value == 2);
+    });
+}
+
+#[test]
+fn loom_sync_sink_clone_during_cycle(){
+    loom::model(|| {
+        let (mut source, sink) = signal::sync::create::<u32>();
+        assert_eq!(State::Ready, source.send(&1));
+
+        let t1 = thread::spawn(move || {
+            let clone = sink.clone();
+            clone.receive();
+            sink.receive();
+            (sink, clone)
+        });
+
+        source.send(&2);
+        let (sink, clone) = t1.join().expect("completion");
+
+        // once all sinks have received the latest value, the source is not blocked
+        sink.receive();
+        clone.receive();
+        assert_eq!(State::Ready, source.send(&3));
+        assert_eq!(sink.receive(), 3);
+        assert_eq!(clone.receive(), 3);
+        assert_eq!(State::Ready, source.send(&4));
+    });
+}
+
+#[test]
+fn loom_sync_sink_drop_during_cycle(){
+    loom::model(|| {
+        let (mut source, sink) = signal::sync::create::<u32>();
+        let other = sink.clone();
+        assert_eq!(State::Ready, source.send(&1));
+
+        // the dropped sink takes its pending acknowledge with it
+        let t1 = thread::spawn(move || drop(other));
+
+        assert_eq!(sink.receive(), 1);
+        let state = source.send(&2);
+        t1.join().expect("completion");
+        if state == State::Receiving {
+            assert_eq!(State::Ready, source.send(&2));
+        }
+        assert_eq!(sink.receive(), 2);
+        assert_eq!(State::Ready, source.send(&3));
+    });
+}
+
+#[test]
+fn loom_sink_is_connected_during_source_drop(){
+    loom::model(|| {
+        let (mut source, sink) = signal::create::<u32>();
+        source.send(&1);
+
+        let t1 = thread::spawn(move || drop(source));
+
+        // the heartbeat stops before the source disconnects
+        if !sink.is_connected() {
+            assert!(!sink.heartbeat().is_alive());
+        }
+        assert_eq!(sink.receive(), 1);
+        t1.join().expect("completion");
+        assert!(!sink.is_connected());
+        assert_eq!(sink.receive(), 1);
+    });
+}
@@ -0,0 +1,133 @@
+//! A subset of the signal tests, small enough to run under Miri. Run it with `tests/miri.sh`,
+//! which checks with stacked and with tree borrows.
+
+use atomx::signal;
+use signal::sync::State;
+use std::thread;
+
+#[cfg(miri)]
+const ITERATIONS: usize = 20;
+#[cfg(not(miri))]
+const ITERATIONS: usize = 2000;
+
+#[derive(Clone, Default)]
+struct Dummy { data: Vec<u64>, checksum: u64 }
+
+impl Dummy {
+    fn new(seed: u64) -> Self {
+        let data: Vec<u64> = (seed..seed + 8).collect();
+        let checksum = data.iter().fold(0, |checksum, x| (checksum << 1) ^ x);
+        Dummy { data, checksum }
+    }
+
+    fn verify(&self) {
+        assert_eq!(self.data.iter().fold(0, |checksum, x| (checksum << 1) ^ x), self.checksum);
+    }
+}
+
+#[test]
+fn signal_concurrent_send_and_process() {
+    let (mut source, sink) = signal::create::<Dummy>();
+    let readers: Vec<_> = (0..2).map(|_| {
+        let sink = sink.clone();
+        thread::spawn(move || {
+            for _ in 0..ITERATIONS {
+                sink.process(&mut |dummy| dummy.verify());
+            }
+        })
+    }).collect();
+    for i in 0..ITERATIONS as u64 {
+        source.send(&Dummy::new(i));
+        source.modify(&mut |dummy| *dummy = Dummy::new(i + 1));
+    }
+    readers.into_iter().for_each(|reader| reader.join().expect("consistent reads"));
+}
+
+#[test]
+fn sink_reads_after_source_dropped() {
+    let (mut source, sink) = signal::create::<Dummy>();
+    source.send(&Dummy::new(3));
+    let reader = {
+        let sink = sink.clone();
+        thread::spawn(move || {
+            for _ in 0..ITERATIONS {
+                sink.process(&mut |dummy| dummy.verify());
+            }
+            sink.receive()
+        })
+    };
+    drop(source);
+    sink.receive().verify();
+    assert_eq!(reader.join().expect("consistent reads").data[0], 3);
+}
+
+#[test]
+fn sync_signal_concurrent_send_and_receive() {
+    let (mut source, sink) = signal::sync::create::<Dummy>();
+    let reader = thread::spawn(move || {
+        let mut received = 0;
+        while received < ITERATIONS {
+            if sink.changed() {
+                sink.receive().verify();
+                received += 1;
+            }
+            if !sink.is_connected() {
+                break
+            }
+            thread::yield_now();
+        }
+    });
+    let mut i = 0;
+    while !reader.is_finished() {
+        if source.send(&Dummy::new(i)) == State::Ready {
+            i += 1;
+        }
+        thread::yield_now();
+    }
+    reader.join().expect("consistent reads");
+}
+
+#[test]
+fn mp_signal_concurrent_modify() {
+    let (source, sink) = signal::mp::create::<Dummy>();
+    let writers: Vec<_> = (0..2).map(|seed| {
+        let mut source = source.clone();
+        thread::spawn(move || {
+            for i in 0..ITERATIONS as u64 {
+                source.modify(&mut |dummy| *dummy = Dummy::new(seed * 1000 + i));
+            }
+        })
+    }).collect();
+    for _ in 0..ITERATIONS {
+        sink.process(&mut |dummy| if !dummy.data.is_empty() { dummy.verify() });
+    }
+    writers.into_iter().for_each(|writer| writer.join().expect("writes"));
+}
+
+#[test]
+fn arc_signal_loaded_value_outlives_source() {
+    let (mut source, sink) = signal::arc::create::<Dummy>();
+    source.publish(std::sync::Arc::new(Dummy::new(1)));
+    let loaded = sink.load();
+    source.publish(std::sync::Arc::new(Dummy::new(2)));
+    drop(source);
+    loaded.verify();
+    assert_eq!(sink.load().data[0], 2);
+}
+
+#[test]
+fn static_signal_concurrent_send_and_receive() {
+    static SIGNAL: signal::StaticSignal<(u64, u64), 3> = signal::StaticSignal::new((0, 0));
+    let reader = thread::spawn(|| {
+        let sink = SIGNAL.sink();
+        for _ in 0..ITERATIONS {
+            let (a, b) = sink.receive();
+            assert_eq!(a, b);
+        }
+    });
+    let mut source = SIGNAL.source().expect("single source");
+    for i in 0..ITERATIONS as u64 {
+        source.send(&(i, i));
+    }
+    reader.join().expect("consistent reads");
+}
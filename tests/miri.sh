@@ -0,0 +1,15 @@
+#!/bin/bash
+# Runs the Miri test subset with stacked and with tree borrows, a few seeds each to vary the
+# thread interleavings. Requires a nightly toolchain with the miri component.
+
+set -e
+
+SEEDS="${SEEDS:-0..8}"
+
+for BORROWS in "" "-Zmiri-tree-borrows"
+do
+    echo "cargo miri test --test miri $BORROWS"
+    MIRIFLAGS="$BORROWS -Zmiri-many-seeds=$SEEDS" \
+    cargo +nightly miri test \
+        --test miri
+done
@@ -0,0 +1,196 @@
+//! A reference model of signals, shared by the property tests and the fuzz target.
+//! Random sequences of operations are applied to a signal and to the model, after each
+//! operation the observations of both need to agree.
+
+use atomx::signal::{self, sync::State};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Op {
+    Send(u32),
+    Modify(u32),
+    NewSink,
+    Clone(usize),
+    Drop(usize),
+    Receive(usize),
+    Process(usize),
+    Changed(usize),
+}
+
+/// Decode a sequence of operations from raw bytes, two bytes per operation.
+pub fn decode(data: &[u8]) -> Vec<Op> {
+    data.chunks_exact(2).map(|op| {
+        let arg = op[1];
+        match op[0] % 8 {
+            0 => Op::Send(arg as u32),
+            1 => Op::Modify(arg as u32),
+            2 => Op::NewSink,
+            3 => Op::Clone(arg as usize),
+            4 => Op::Drop(arg as usize),
+            5 => Op::Receive(arg as usize),
+            6 => Op::Process(arg as usize),
+            _ => Op::Changed(arg as usize),
+        }
+    }).collect()
+}
+
+/// The expected state of a sink, the generation of the value it has read last and the one it
+/// has joined at.
+#[derive(Clone, Copy)]
+struct Model {
+    last: Option<u64>,
+    joined: u64,
+}
+
+impl Model {
+    fn new(generation: u64) -> Self {
+        Model { last: None, joined: generation }
+    }
+
+    /// A new sink has not read anything, so it sees the signal as changed.
+    fn changed(&self, generation: u64) -> bool {
+        self.last != Some(generation)
+    }
+
+    /// A sync sink is counted for the publishes after it joined, until it has read them.
+    fn pending(&self, generation: u64) -> bool {
+        self.joined < generation && self.changed(generation)
+    }
+}
+
+/// The sinks of a signal under test, with the operations common to plain and sync sinks.
+trait Sinks {
+    type Sink;
+    fn receive(sink: &Self::Sink) -> u32;
+    fn process(sink: &Self::Sink) -> u32;
+    fn changed(sink: &Self::Sink) -> bool;
+    fn clone(sink: &Self::Sink) -> Self::Sink;
+}
+
+struct Plain;
+impl Sinks for Plain {
+    type Sink = signal::Sink<u32>;
+    fn receive(sink: &Self::Sink) -> u32 { sink.receive() }
+    fn process(sink: &Self::Sink) -> u32 {
+        let mut value = 0;
+        sink.process(&mut |v| value = *v);
+        value
+    }
+    fn changed(sink: &Self::Sink) -> bool { sink.changed() }
+    fn clone(sink: &Self::Sink) -> Self::Sink { sink.clone() }
+}
+
+struct Sync;
+impl Sinks for Sync {
+    type Sink = signal::sync::Sink<u32>;
+    fn receive(sink: &Self::Sink) -> u32 { sink.receive() }
+    fn process(sink: &Self::Sink) -> u32 {
+        let mut value = 0;
+        sink.process(&mut |v| value = *v);
+        value
+    }
+    fn changed(sink: &Self::Sink) -> bool { sink.changed() }
+    fn clone(sink: &Self::Sink) -> Self::Sink { sink.clone() }
+}
+
+/// Apply the sink operations, which are the same for both kinds of signals.
+/// A clone is a new sink, it does not take over the reads of the cloned sink.
+fn apply_sink_op<S: Sinks>(op: Op, sinks: &mut Vec<(S::Sink, Model)>, latest: u32, generation: u64) {
+    let pick = |index: usize, len: usize| (len > 0).then(|| index % len);
+    match op {
+        Op::Clone(index) => if let Some(index) = pick(index, sinks.len()) {
+            let clone = S::clone(&sinks[index].0);
+            sinks.push((clone, Model::new(generation)));
+        },
+        Op::Drop(index) => if let Some(index) = pick(index, sinks.len()) {
+            sinks.swap_remove(index);
+        },
+        Op::Receive(index) | Op::Process(index) => if let Some(index) = pick(index, sinks.len()) {
+            let (sink, model) = &mut sinks[index];
+            let value = match op {
+                Op::Receive(_) => S::receive(sink),
+                _ => S::process(sink),
+            };
+            assert_eq!(value, latest, "a sink reads the latest value");
+            model.last = Some(generation);
+        },
+        Op::Changed(index) => if let Some(index) = pick(index, sinks.len()) {
+            let (sink, model) = &sinks[index];
+            assert_eq!(S::changed(sink), model.changed(generation), "changed until the latest value is read");
+        },
+        Op::Send(_) | Op::Modify(_) | Op::NewSink => unreachable!(),
+    }
+}
+
+/// Run the operations on a plain signal.
+pub fn run_plain(ops: &[Op]) {
+    let (mut source, sink) = signal::create::<u32>();
+    let mut sinks = vec![(sink, Model::new(0))];
+    let (mut latest, mut generation) = (0, 0);
+    for &op in ops {
+        match op {
+            Op::Send(value) | Op::Modify(value) => {
+                let state = match op {
+                    Op::Send(_) => source.send(&value),
+                    _ => source.modify(&mut |v| *v = value),
+                };
+                let expected = match sinks.is_empty() {
+                    true => State::AllGone,
+                    false => State::Ready,
+                };
+                assert_eq!(state, expected);
+                latest = value;
+                generation += 1;
+            }
+            Op::NewSink => sinks.push((source.sink(), Model::new(generation))),
+            op => apply_sink_op::<Plain>(op, &mut sinks, latest, generation),
+        }
+        assert_eq!(source.sink_count() as usize, sinks.len());
+    }
+}
+
+/// Returns the state of a sync source, which waits for the sinks existing at the latest publish
+/// until they have read it. Sinks joining afterwards are not waited for, sinks which are dropped
+/// are not waited for anymore.
+fn sync_state(sinks: &[(signal::sync::Sink<u32>, Model)], generation: u64) -> State {
+    match sinks.is_empty() {
+        true => State::AllGone,
+        false if sinks.iter().any(|(_, model)| model.pending(generation)) => State::Receiving,
+        false => State::Ready,
+    }
+}
+
+/// Run the operations on a synced signal. A value is only published once the sinks counted for
+/// the previous value have read it, see [sync_state].
+pub fn run_sync(ops: &[Op]) {
+    let (mut source, sink) = signal::sync::create::<u32>();
+    let mut sinks = vec![(sink, Model::new(0))];
+    let (mut latest, mut generation) = (0, 0);
+    for &op in ops {
+        match op {
+            Op::Send(value) | Op::Modify(value) => {
+                let expected = sync_state(&sinks, generation);
+                let state = match op {
+                    Op::Send(_) => source.send(&value),
+                    _ => source.modify(&mut |v| *v = value),
+                };
+                assert_eq!(state, expected);
+                if state == State::Ready {
+                    latest = value;
+                    generation += 1;
+                }
+            }
+            Op::NewSink => sinks.push((source.sink(), Model::new(generation))),
+            op => apply_sink_op::<Sync>(op, &mut sinks, latest, generation),
+        }
+    }
+    // once all sinks have read the latest value, the source can publish again
+    for (sink, model) in &mut sinks {
+        assert_eq!(sink.receive(), latest);
+        model.last = Some(generation);
+    }
+    let expected = match sinks.is_empty() {
+        true => State::AllGone,
+        false => State::Ready,
+    };
+    assert_eq!(source.send(&0), expected);
+}
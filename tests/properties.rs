@@ -0,0 +1,41 @@
+//! Property tests of the signal invariants against a reference model, see `model`.
+
+mod model;
+
+use model::Op;
+use proptest::{collection::vec, prelude::*};
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        any::<u32>().prop_map(Op::Send),
+        any::<u32>().prop_map(Op::Modify),
+        Just(Op::NewSink),
+        any::<usize>().prop_map(Op::Clone),
+        any::<usize>().prop_map(Op::Drop),
+        any::<usize>().prop_map(Op::Receive),
+        any::<usize>().prop_map(Op::Process),
+        any::<usize>().prop_map(Op::Changed),
+    ]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(if cfg!(miri) { 4 } else { 256 }))]
+
+    #[test]
+    fn plain_signal_follows_model(ops in vec(op(), 0..64)) {
+        model::run_plain(&ops);
+    }
+
+    #[test]
+    fn sync_signal_follows_model(ops in vec(op(), 0..64)) {
+        model::run_sync(&ops);
+    }
+}
+
+#[test]
+fn decoded_operations_follow_model() {
+    let data: Vec<u8> = (0..=255u8).flat_map(|x| [x, x.wrapping_mul(31)]).collect();
+    let ops = model::decode(&data);
+    model::run_plain(&ops);
+    model::run_sync(&ops);
+}
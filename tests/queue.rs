@@ -0,0 +1,55 @@
+#![cfg(feature = "std")]
+
+use atomx::queue::spsc;
+
+#[test]
+fn no_value_is_lost_on_concurrent_access() {
+    let (mut source, mut sink) = spsc::channel::<u64>(64);
+
+    let producer = std::thread::spawn(move || {
+        for value in 0..100_000 {
+            source.push(value);
+        }
+    });
+
+    let consumer = std::thread::spawn(move || {
+        let mut expected = 0;
+        while let Some(value) = sink.pop() {
+            assert_eq!(value, expected);
+            expected += 1;
+        }
+        expected
+    });
+
+    producer.join().expect("Couldn't join on the producer thread");
+    assert_eq!(consumer.join().expect("Couldn't join on the consumer thread"), 100_000);
+}
+
+#[test]
+fn batches_are_lossless_on_concurrent_access() {
+    let (mut source, mut sink) = spsc::channel::<u64>(16);
+
+    let producer = std::thread::spawn(move || {
+        let values: Vec<u64> = (0..100_000).collect();
+        let mut sent = 0;
+        while sent < values.len() {
+            let count = source.push_slice(&values[sent..(sent + 7).min(values.len())]).expect("sink exists");
+            if count == 0 {
+                std::thread::yield_now();
+            }
+            sent += count;
+        }
+    });
+
+    let mut received = Vec::new();
+    let mut buffer = [0; 5];
+    while received.len() < 100_000 {
+        let count = sink.pop_into(&mut buffer);
+        received.extend_from_slice(&buffer[..count]);
+        if count == 0 {
+            std::thread::yield_now();
+        }
+    }
+    producer.join().expect("Couldn't join on the producer thread");
+    assert!(received.iter().copied().eq(0..100_000));
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "record")]
+
+use atomx::signal::{self, record::{Recorder, Replayer, Timing}};
+use std::time::{Duration, Instant};
+
+#[test]
+fn recorded_publishes_are_replayed() {
+    let (mut commands, _command_sink) = signal::create::<String>();
+    let (mut speeds, speed_sink) = signal::create::<f64>();
+
+    // record a source directly and a tapped sink
+    let mut recorder = Recorder::new(Vec::new()).expect("recorder");
+    recorder.send(0, &mut commands, &String::from("start")).expect("recorded");
+    speeds.send(&1.5);
+    assert!(recorder.tap(1, &speed_sink).expect("recorded"));
+    assert!(!recorder.tap(1, &speed_sink).expect("nothing new"));
+    std::thread::sleep(Duration::from_millis(20));
+    recorder.modify(0, &mut commands, &mut |command| *command = String::from("stop")).expect("recorded");
+    let log = recorder.into_inner();
+
+    let records: Vec<_> = Replayer::new(&log[..]).expect("replayer")
+        .collect::<Result<_, _>>().expect("records");
+    assert_eq!(records.len(), 3);
+    assert_eq!((records[0].channel, records[0].generation), (0, 1));
+    assert_eq!(records[0].value::<String>().expect("value"), "start");
+    assert_eq!((records[1].channel, records[1].generation), (1, 1));
+    assert_eq!(records[1].value::<f64>().expect("value"), 1.5);
+    assert_eq!((records[2].channel, records[2].generation), (0, 2));
+    assert_eq!(records[2].value::<String>().expect("value"), "stop");
+    assert!(records[2].time - records[1].time >= Duration::from_millis(20));
+
+    // replay into fresh sources with the original timing
+    let (mut replayed, replayed_sink) = signal::create::<String>();
+    let start = Instant::now();
+    let count = Replayer::new(&log[..]).expect("replayer")
+        .replay_into(0, &mut replayed, Timing::Original).expect("replayed");
+    assert_eq!(count, 2);
+    assert!(start.elapsed() >= Duration::from_millis(20));
+    assert_eq!(replayed_sink.receive(), "stop");
+
+    // replay scaled, ten times as fast
+    let start = Instant::now();
+    let mut channels = vec![];
+    Replayer::new(&log[..]).expect("replayer")
+        .replay(Timing::Scaled(10.0), &mut |record| { channels.push(record.channel); Ok(()) })
+        .expect("replayed");
+    assert_eq!(channels, vec![0, 1, 0]);
+    assert!(start.elapsed() >= Duration::from_millis(2));
+}
@@ -0,0 +1,55 @@
+#![cfg(all(unix, feature = "shm"))]
+
+use atomx::signal::shm;
+use std::{process::{Command, Stdio}, time::{Duration, Instant}};
+
+const NAME_VAR: &str = "ATOMX_SHM_TEST_NAME";
+const LAST: u64 = 10_000;
+
+/// Runs in a child process, started by the test below.
+#[test]
+#[ignore = "runs as child process of cross_process_signal"]
+fn cross_process_sink() {
+    let name = std::env::var(NAME_VAR).expect("name of the signal");
+    let sink = shm::Sink::<[u64; 8]>::open(&name).expect("opened");
+    loop {
+        let value = sink.receive();
+        assert!(value.iter().all(|v| *v == value[0]), "torn value {:?}", value);
+        if value[0] == LAST {
+            break
+        }
+    }
+}
+
+#[test]
+fn cross_process_signal() {
+    let name = format!("atomx-cross-process-{}", std::process::id());
+    let mut source = shm::Source::<[u64; 8]>::create(&name).expect("created");
+
+    let mut children: Vec<_> = (0..2).map(|_| {
+        Command::new(std::env::current_exe().expect("test binary"))
+            .args(["cross_process_sink", "--exact", "--ignored", "--quiet"])
+            .env(NAME_VAR, &name)
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("child process")
+    }).collect();
+
+    // a child which fails to open the signal would let the wait hang
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while source.sink_count() < 2 {
+        if Instant::now() > deadline {
+            let status: Vec<_> = children.iter_mut().map(|child| child.try_wait()).collect();
+            children.iter_mut().for_each(|child| { let _ = child.kill(); });
+            panic!("sinks did not open the signal, children exited with {:?}", status);
+        }
+        std::thread::yield_now();
+    }
+    for value in 1..=LAST {
+        source.send(&[value; 8]);
+    }
+
+    for mut child in children {
+        assert!(child.wait().expect("child exit").success());
+    }
+}
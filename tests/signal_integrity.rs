@@ -44,7 +44,7 @@ impl Dummy {
         let checksum1 = self.checksum;
         if checksum0 != checksum1{
             println!("{:?} fail, last_value:{}", std::thread::current().id(), last_value);
-            assert!(checksum0 != checksum1);
+            assert_eq!(checksum0, checksum1);
         }
     }
 }
@@ -150,3 +150,48 @@ fn data_integrity_on_concurrent_access_signal() {
     t2.join().expect("Couldn't join on the associated thread 2");
 
 }
+
+#[test]
+fn data_integrity_on_concurrent_access_mp_signal() {
+    let (source, sink) = signal::mp::create::<Dummy>();
+    let mut handles = vec![];
+
+    // run the consumer
+    for _thread in 0..4 {
+        let sink = sink.clone();
+        handles.push(std::thread::spawn(move || {
+            for _ in 0..100000 {
+                sink.process(&mut |dummy|{
+                    dummy.verify();
+                });
+            }
+        }));
+    }
+
+    // run the producers
+    for thread in 0..2 {
+        let mut source = source.clone();
+        handles.push(std::thread::spawn(move || {
+            let mut idx = thread;
+            loop {
+                let state = source.modify(&mut |dummy| {
+                    *dummy = match idx%3 {
+                        2 => Dummy::default_a(),
+                        1 => Dummy::default_b(),
+                        _ => Dummy::default_c(),
+                    };
+                });
+                if state == signal::sync::State::AllGone {
+                    break
+                }
+                idx += 1;
+            }
+        }));
+    }
+
+    drop(source);
+    drop(sink);
+    for handle in handles {
+        handle.join().expect("Couldn't join on the associated thread");
+    }
+}